@@ -0,0 +1,290 @@
+use std::{cell::Cell, rc::Rc, time::{Duration, Instant}};
+
+use crate::frame_notifier::{FrameListener, FrameNotifier};
+
+/// The time source animations run on.
+/// A clock keeps its own time, which only moves when it is ticked (system clocks) or advanced by hand (manual clocks),
+/// so it can be paused, slowed down or stepped through frame by frame.
+#[derive(Debug, Clone)]
+pub struct AnimationClock {
+    inner: Rc<AnimationClockInner>
+}
+
+#[derive(Debug)]
+pub struct AnimationClockInner {
+    kind: ClockKind,
+    origin: Instant,
+    elapsed: Cell<Duration>,
+    /// The elapsed time the listeners last ran at
+    last_run: Cell<Duration>,
+    last_tick: Cell<Instant>,
+    paused: Cell<bool>,
+    time_scale: Cell<f32>,
+    notifier: FrameNotifier,
+    /// Whether this clock is in the thread's list of clocks the backend ticks
+    registered: Cell<bool>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockKind {
+    /// Follows the wall clock every time it is ticked.
+    System,
+    /// Only moves when `advance` is called, for deterministic tests.
+    Manual
+}
+
+impl AnimationClock {
+    pub fn system() -> Self {
+        Self::new(ClockKind::System)
+    }
+
+    pub fn manual() -> Self {
+        Self::new(ClockKind::Manual)
+    }
+
+    fn new(kind: ClockKind) -> Self {
+        let now = Instant::now();
+
+        Self {
+            inner: Rc::new(AnimationClockInner {
+                kind,
+                origin: now,
+                elapsed: Cell::new(Duration::ZERO),
+                last_run: Cell::new(Duration::ZERO),
+                last_tick: Cell::new(now),
+                paused: Cell::new(false),
+                time_scale: Cell::new(1.0),
+                notifier: FrameNotifier::new(),
+                registered: Cell::new(false)
+            })
+        }
+    }
+
+    /// The clock of the current thread, which the backend ticks once per frame.
+    pub fn local() -> Self {
+        crate::LOCAL_ANIMATION_CLOCK.with(|clock| clock.borrow().clone())
+    }
+
+    /// Replaces the clock of the current thread. Only affects animations created afterwards,
+    /// the ones running on the previous clock keep being ticked until they finish.
+    pub fn set_local(clock: AnimationClock) {
+        crate::LOCAL_ANIMATION_CLOCK.with(|local| *local.borrow_mut() = clock);
+    }
+
+    pub fn kind(&self) -> ClockKind {
+        self.inner.kind
+    }
+
+    /// The current time of this clock. Only comparable to other instants of the same clock.
+    pub fn now(&self) -> Instant {
+        self.inner.origin + self.inner.elapsed.get()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.inner.elapsed.get()
+    }
+
+    pub fn pause(&self) {
+        self.set_paused(true);
+    }
+
+    pub fn resume(&self) {
+        self.set_paused(false);
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.sync();
+        self.inner.paused.set(paused);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.inner.paused.get()
+    }
+
+    /// Sets how fast this clock runs compared to real time, e.g. 0.1 for a tenth of the speed.
+    pub fn set_time_scale(&self, scale: f32) {
+        self.sync();
+        self.inner.time_scale.set(scale.max(0.0));
+    }
+
+    pub fn time_scale(&self) -> f32 {
+        self.inner.time_scale.get()
+    }
+
+    /// Adds a listener, which the backend runs every frame until it expires.
+    pub fn add(&self, listener: FrameListener) {
+        self.inner.notifier.add(listener);
+
+        if !self.inner.registered.replace(true) {
+            crate::LOCAL_ACTIVE_CLOCKS.with(|clocks| clocks.borrow_mut().push(self.clone()));
+        }
+    }
+
+    /// Ticks every clock of the current thread that has listeners, not only the local one,
+    /// and runs the listeners of the deprecated `LOCAL_FRAME_NOTIFIER`.
+    /// Returns true if any listener ran.
+    pub fn tick_all() -> bool {
+        // Taken out, so listeners can start animations on other clocks while running
+        let clocks = crate::LOCAL_ACTIVE_CLOCKS.with(|clocks| std::mem::take(&mut *clocks.borrow_mut()));
+        let mut ran = false;
+
+        let mut active = clocks.into_iter().filter(|clock| {
+            ran |= clock.tick();
+
+            let active = clock.is_active();
+            clock.inner.registered.set(active);
+            active
+        }).collect::<Vec<_>>();
+
+        crate::LOCAL_ACTIVE_CLOCKS.with(|clocks| {
+            let mut clocks = clocks.borrow_mut();
+            active.append(&mut clocks);
+            *clocks = active;
+        });
+
+        // Follows the wall clock, like before clocks existed
+        #[allow(deprecated)]
+        crate::LOCAL_FRAME_NOTIFIER.with(|notifier| if !notifier.is_empty() {
+            notifier.run(Instant::now());
+            ran = true;
+        });
+
+        ran
+    }
+
+    /// Returns true if animations are running on a clock that moves by itself, so the backend has to keep drawing frames.
+    /// Paused and manual clocks only move once they are resumed or advanced.
+    pub fn any_running() -> bool {
+        #[allow(deprecated)]
+        let legacy = crate::LOCAL_FRAME_NOTIFIER.with(|notifier| !notifier.is_empty());
+
+        legacy || crate::LOCAL_ACTIVE_CLOCKS.with(|clocks| clocks.borrow().iter().any(|clock| {
+            clock.kind() == ClockKind::System && !clock.is_paused() && clock.time_scale() > 0.0
+        }))
+    }
+
+    /// Returns true if there are animations running on this clock.
+    pub fn is_active(&self) -> bool {
+        !self.inner.notifier.is_empty()
+    }
+
+    /// Catches a system clock up with the wall clock and runs all listeners, if its time changed since they last ran.
+    /// Manual clocks don't move when ticked, use `advance` to move them.
+    /// Returns true if any listener ran, meaning animated values may have changed.
+    pub fn tick(&self) -> bool {
+        self.sync();
+        self.run()
+    }
+
+    /// Moves the clock forward by `delta` (scaled, unless paused) and runs all listeners.
+    /// Returns true if any listener ran.
+    pub fn advance(&self, delta: Duration) -> bool {
+        self.sync();
+        self.forward(delta);
+        self.run()
+    }
+
+    fn run(&self) -> bool {
+        // The listeners already ran at this time, e.g. while the clock is paused
+        let elapsed = self.elapsed();
+        if !self.is_active() || self.inner.last_run.replace(elapsed) == elapsed {
+            return false;
+        }
+
+        self.inner.notifier.run(self.now());
+        true
+    }
+
+    fn sync(&self) {
+        if self.inner.kind == ClockKind::System {
+            let now = Instant::now();
+            let real = now.saturating_duration_since(self.inner.last_tick.replace(now));
+            self.forward(real);
+        }
+    }
+
+    fn forward(&self, delta: Duration) {
+        if !self.is_paused() {
+            let scaled = delta.mul_f32(self.time_scale());
+            self.inner.elapsed.set(self.inner.elapsed.get() + scaled);
+        }
+    }
+}
+
+impl Default for AnimationClock {
+    fn default() -> Self {
+        Self::system()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use crate::{animations::{easings::EasingFunction, motion::Motion}, signals::{Signal, SignalTrait}};
+
+    use super::AnimationClock;
+
+    #[test]
+    fn manual_clock_steps_frame_by_frame() {
+        let clock = AnimationClock::manual();
+        let target = Signal::new(0.0f32);
+        let animated = target.animate_with(&clock, Duration::from_millis(100), EasingFunction::Linear, Motion::Essential);
+
+        target.set(100.0);
+        assert_eq!(*animated.get(), 0.0);
+
+        for expected in [25.0, 50.0, 75.0, 100.0] {
+            assert!(clock.advance(Duration::from_millis(25)));
+            assert_eq!(*animated.get(), expected);
+        }
+
+        assert!(!clock.is_active());
+        assert!(!clock.advance(Duration::from_millis(25)));
+        assert_eq!(*animated.get(), 100.0);
+    }
+
+    #[test]
+    fn ticks_clocks_replaced_as_local() {
+        let previous = AnimationClock::system();
+        AnimationClock::set_local(previous.clone());
+
+        let target = Signal::new(0.0f32);
+        let animated = target.animate_essential(Duration::from_secs(60), EasingFunction::Linear);
+        target.set(100.0);
+
+        AnimationClock::set_local(AnimationClock::manual());
+        thread::sleep(Duration::from_millis(5));
+
+        // Still ticked by the backend, even though it isn't the local clock anymore
+        assert!(AnimationClock::any_running());
+        assert!(AnimationClock::tick_all());
+        assert!(*animated.get() > 0.0);
+
+        previous.pause();
+        AnimationClock::tick_all();
+        let paused = *animated.get();
+        thread::sleep(Duration::from_millis(5));
+
+        assert!(!AnimationClock::any_running());
+        assert!(!AnimationClock::tick_all());
+        assert_eq!(*animated.get(), paused);
+    }
+
+    #[test]
+    fn manual_clocks_dont_need_frames() {
+        let clock = AnimationClock::manual();
+        let target = Signal::new(0.0f32);
+        let _animated = target.animate_with(&clock, Duration::from_millis(100), EasingFunction::Linear, Motion::Essential);
+        target.set(100.0);
+
+        assert!(clock.is_active());
+        assert!(!AnimationClock::any_running());
+        assert!(!AnimationClock::tick_all());
+        assert!(!clock.tick());
+
+        assert!(clock.advance(Duration::from_millis(10)));
+        assert!(!clock.advance(Duration::ZERO));
+    }
+}
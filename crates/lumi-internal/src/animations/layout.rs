@@ -0,0 +1,80 @@
+use std::{cell::Cell, fmt::Debug, rc::Rc, time::Duration};
+
+use crate::{frame_notifier::FrameListener, geometry::Bounds, signals::{Signal, SignalTrait}};
+
+use super::{clock::AnimationClock, easings::EasingFunction, motion::Motion};
+
+
+/// Tweens a widget from its old to its new bounds whenever its geometry changes,
+/// instead of letting it jump there.
+#[derive(Clone)]
+pub struct LayoutAnimation {
+    pub duration: Duration,
+    pub easing: Rc<EasingFunction>,
+    pub motion: Motion
+}
+
+impl LayoutAnimation {
+    pub fn new(duration: Duration, easing: EasingFunction) -> Self {
+        Self {
+            duration,
+            easing: Rc::new(easing),
+            motion: Motion::Reducible
+        }
+    }
+
+    /// Keeps animating while reduced motion is enabled.
+    pub fn essential(self) -> Self {
+        Self { motion: Motion::Essential, ..self }
+    }
+
+    /// Returns a signal following `target`, which moves to every new value over the duration of this animation.
+    /// A change in the middle of an animation continues from wherever the bounds currently are.
+    pub fn animate(&self, target: &Signal<Bounds>) -> Signal<Bounds> {
+        let shown = Signal::new(*target.get());
+        let generation = Rc::new(Cell::new(0u64));
+        let (duration, easing, motion, cloned) = (self.duration, self.easing.clone(), self.motion, shown.clone());
+        let clock = AnimationClock::local();
+
+        target.subscribe(move |end| {
+            let (start, end) = (*cloned.get(), *end);
+
+            let current = generation.get() + 1;
+            generation.set(current);
+
+            if start == end {
+                return;
+            }
+
+            if !motion.allowed() {
+                cloned.set(end);
+                return;
+            }
+
+            let (generation, easing, shown) = (generation.clone(), easing.clone(), cloned.clone());
+            let start_time = clock.now();
+
+            clock.add(FrameListener::new(move |time| {
+                if generation.get() != current {
+                    return;
+                }
+
+                let progress = (time - start_time).div_duration_f32(duration).min(1.0);
+                let eased: f32 = easing.calculate(&0.0f32, &1.0f32, progress);
+
+                shown.set(start.lerp(end, eased));
+            }, start_time + duration));
+        });
+
+        shown
+    }
+}
+
+impl Debug for LayoutAnimation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LayoutAnimation")
+        .field("duration", &self.duration)
+        .field("motion", &self.motion)
+        .finish_non_exhaustive()
+    }
+}
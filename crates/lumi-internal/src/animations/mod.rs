@@ -1 +1,5 @@
-pub mod easings;
\ No newline at end of file
+pub mod easings;
+pub mod clock;
+pub mod transition;
+pub mod layout;
+pub mod motion;
\ No newline at end of file
@@ -0,0 +1,23 @@
+use crate::signals::{Signal, SignalTrait};
+
+/// Whether an animation may be skipped when the user asked for reduced motion.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Motion {
+    /// Jumps to its end value (or cross-fades) while reduced motion is enabled.
+    #[default]
+    Reducible,
+    /// Conveys information, like a progress indicator, and always animates.
+    Essential
+}
+
+impl Motion {
+    /// Returns true if an animation with this kind of motion should currently move.
+    pub fn allowed(self) -> bool {
+        self == Motion::Essential || !*reduced_motion().get()
+    }
+}
+
+/// The reduced motion preference of the current thread, shared by every animation on it.
+pub fn reduced_motion() -> Signal<bool> {
+    crate::LOCAL_REDUCED_MOTION.with(|reduced| reduced.clone())
+}
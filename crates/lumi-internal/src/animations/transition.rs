@@ -0,0 +1,164 @@
+use std::{cell::Cell, fmt::Debug, rc::Rc, time::Duration};
+
+use crate::{frame_notifier::FrameListener, geometry::Transform, signals::{Signal, SignalTrait}};
+
+use super::{clock::AnimationClock, easings::EasingFunction, motion::{reduced_motion, Motion}};
+
+
+/// How a subtree appears and disappears when a dynamic element swaps its children.
+#[derive(Clone)]
+pub struct Transition {
+    pub enter: TransitionEffect,
+    pub exit: TransitionEffect,
+    pub duration: Duration,
+    pub easing: Rc<EasingFunction>,
+    /// Whether the children built first should play their enter transition as well.
+    pub appear: bool,
+    /// Reducible transitions cross-fade instead while reduced motion is enabled.
+    pub motion: Motion
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransitionEffect {
+    None,
+    Fade,
+    /// Slides in from / out to the given offset in pixels.
+    Slide { x: f32, y: f32 },
+    /// Grows from / shrinks to the given factor around the subtree's center.
+    Scale(f32)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionPhase {
+    Entering,
+    Exiting
+}
+
+/// The progress of one subtree's transition, 0.0 being fully hidden and 1.0 fully shown.
+#[derive(Debug, Clone)]
+pub struct TransitionState {
+    pub progress: Signal<f32>,
+    pub phase: Signal<TransitionPhase>,
+    pub pivot: Signal<(f32, f32)>,
+    generation: Rc<Cell<u64>>
+}
+
+impl Transition {
+    pub fn new(enter: TransitionEffect, exit: TransitionEffect, duration: Duration, easing: EasingFunction) -> Self {
+        Self {
+            enter,
+            exit,
+            duration,
+            easing: Rc::new(easing),
+            appear: false,
+            motion: Motion::Reducible
+        }
+    }
+
+    pub fn fade(duration: Duration) -> Self {
+        Self::new(TransitionEffect::Fade, TransitionEffect::Fade, duration, EasingFunction::Linear)
+    }
+
+    pub fn appear(self, appear: bool) -> Self {
+        Self { appear, ..self }
+    }
+
+    pub fn motion(self, motion: Motion) -> Self {
+        Self { motion, ..self }
+    }
+
+    pub fn transform(&self, state: &TransitionState) -> Signal<Transform> {
+        let (enter, exit, motion) = (self.enter, self.exit, self.motion);
+        let combined = (state.progress.clone(), state.phase.clone(), state.pivot.clone(), reduced_motion());
+
+        combined.relative(move |(progress, phase, pivot, reduced)| {
+            let effect = match **phase {
+                TransitionPhase::Entering => enter,
+                TransitionPhase::Exiting => exit
+            };
+
+            if **reduced && motion == Motion::Reducible {
+                effect.reduced().transform(**progress, **pivot)
+            } else {
+                effect.transform(**progress, **pivot)
+            }
+        })
+    }
+
+    /// Animates `state` towards the end of `phase` and calls `on_done` once it got there.
+    /// Cancels whatever transition `state` was running before.
+    pub fn start(&self, state: &TransitionState, phase: TransitionPhase, on_done: impl Fn() + 'static) {
+        let generation = state.generation.get() + 1;
+        state.generation.set(generation);
+        state.phase.set(phase);
+
+        let clock = AnimationClock::local();
+        let start_time = clock.now();
+        let from = *state.progress.get();
+        let target = match phase {
+            TransitionPhase::Entering => 1.0,
+            TransitionPhase::Exiting => 0.0
+        };
+        let (duration, easing, state) = (self.duration, self.easing.clone(), state.clone());
+
+        clock.add(FrameListener::new(move |time| {
+            if state.generation.get() != generation {
+                return;
+            }
+
+            let linear = (time - start_time).div_duration_f32(duration).min(1.0);
+            let eased: f32 = easing.calculate(&0.0f32, &1.0f32, linear);
+
+            state.progress.set(from + (target - from) * eased);
+
+            if linear >= 1.0 {
+                on_done();
+            }
+        }, start_time + duration));
+    }
+}
+
+impl Debug for Transition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Transition")
+        .field("enter", &self.enter)
+        .field("exit", &self.exit)
+        .field("duration", &self.duration)
+        .field("appear", &self.appear)
+        .field("motion", &self.motion)
+        .finish_non_exhaustive()
+    }
+}
+
+impl TransitionEffect {
+    /// The effect to use instead while reduced motion is enabled.
+    pub fn reduced(self) -> TransitionEffect {
+        match self {
+            TransitionEffect::None => TransitionEffect::None,
+            _ => TransitionEffect::Fade
+        }
+    }
+
+    pub fn transform(&self, progress: f32, pivot: (f32, f32)) -> Transform {
+        match *self {
+            TransitionEffect::None => Transform::IDENTITY,
+            TransitionEffect::Fade => Transform::opacity(progress),
+            TransitionEffect::Slide { x, y } => Transform::translate(x * (1.0 - progress), y * (1.0 - progress)),
+            TransitionEffect::Scale(from) => {
+                let scale = from + (1.0 - from) * progress;
+                Transform::scale_around(scale, scale, pivot.0, pivot.1)
+            }
+        }
+    }
+}
+
+impl TransitionState {
+    pub fn new(progress: f32) -> Self {
+        Self {
+            progress: Signal::new(progress),
+            phase: Signal::new(TransitionPhase::Entering),
+            pivot: Signal::new((0.0, 0.0)),
+            generation: Rc::new(Cell::new(0))
+        }
+    }
+}
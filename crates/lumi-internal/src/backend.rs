@@ -3,7 +3,7 @@ use std::{cell::{Ref, RefCell}, collections::HashMap, rc::{Rc, Weak}};
 use log::info;
 use lumi2d::{backend::errors::BackendError, prelude::*};
 
-use crate::{custom_event::CustomEvent, elements::{element::ElementTrait, element_builder::{ElementBuilder, ElementBuilderTrait}, window::{Window, WindowInner, WindowState}}};
+use crate::{animations::{clock::AnimationClock, motion}, custom_event::CustomEvent, elements::{element::{Element, ElementTrait}, element_builder::{ElementBuilder, ElementBuilderTrait}, lifecycle::Cleanups, window::{Window, WindowInner, WindowState}}, signals::Signal};
 
 #[derive(Debug, Clone)]
 pub struct Backend {
@@ -25,17 +25,27 @@ impl Backend {
                 windows: RefCell::new(HashMap::new())
             };
 
-            crate::GLOBAL_SENDER.set(backend.backend.sender()).unwrap();
+            crate::set_global_sender(backend.backend.sender());
 
             callback(Self { inner: Rc::new(backend) });
         })
     }
 
     pub(crate) fn create_window_inner(&self, details: WindowDetails, state: WindowState) -> WindowInner {
-        let lumi_win = self.inner.backend.create_window(details);
+        #[cfg(feature = "hot_reload")]
+        if let Some(retained) = crate::hot_reload::take_retained_window() {
+            if retained.has_details(&details) {
+                return retained.reuse(state);
+            }
+
+            // Replaced, so the new details take effect
+            retained.close(&self.renderer_data());
+        }
+
+        let lumi_win = self.inner.backend.create_window(details.clone());
         let renderer = lumi_win.create_renderer(&self.inner.backend).unwrap();
         
-        Window::create_inner(lumi_win, renderer, state)
+        Window::create_inner(lumi_win, renderer, details, state)
     }
 
     pub fn register_font(&self, alias: &str, font_bytes: &[u8]) {
@@ -49,6 +59,10 @@ impl Backend {
     pub fn run_ui(&self, builder: ElementBuilder) {
         let _element = builder.build(self, None);
 
+        self.run_events();
+    }
+
+    pub(crate) fn run_events(&self) {
         self.inner.backend.subscribe_events(|events| {
             let mut grouped: HashMap<WindowId, Vec<WindowEvent>> = HashMap::new();
             
@@ -59,6 +73,7 @@ impl Backend {
                     grouped.insert(window_id, vec![event]);
                 }
             };
+            let mut timers_fired = false;
 
             for event in events {
                 match event {
@@ -70,16 +85,43 @@ impl Backend {
                             append_or_insert(window_id, event);
                         },
                         CustomEvent::Callback(fn_once) => fn_once(),
-                        CustomEvent::Redraw(window) => append_or_insert(window, WindowEvent::Redraw)
+                        CustomEvent::Redraw(window) => append_or_insert(window, WindowEvent::Redraw),
+                        CustomEvent::Timer(id) => {
+                            crate::timer::fire(id);
+                            timers_fired = true;
+                        }
                     },
                 }
             }
 
+            // Timer callbacks can set signals shown in any window. Which ones isn't tracked,
+            // so all windows are redrawn, at most once per batch of events
+            if timers_fired {
+                for window_id in self.inner.windows.borrow().keys() {
+                    append_or_insert(window_id.clone(), WindowEvent::Redraw);
+                }
+            }
+
+            // Ticked once per frame, no matter how many windows received events
+            if AnimationClock::tick_all() {
+                for window_id in self.inner.windows.borrow().keys() {
+                    append_or_insert(window_id.clone(), WindowEvent::Redraw);
+                }
+            }
+
             for (window, events) in grouped.into_iter() {
-                let events = reverse_dedup_enums(events.into_iter());
+                let events = reverse_dedup_enums(sum_scrolls(events));
                 
                 self.resolve_events(window, events);
             }
+
+            // Checked after the events, which may have started or resumed animations.
+            // Paused and manual clocks don't need frames, so the loop can go idle
+            if AnimationClock::any_running() {
+                for window_id in self.inner.windows.borrow().keys() {
+                    crate::global_send(Event::Custom(CustomEvent::Redraw(window_id.clone())));
+                }
+            }
         });
     }
     
@@ -94,6 +136,8 @@ impl Backend {
         );
         if let Some(true) = result {
             self.take_window(&window_id).map(|win| {
+                Cleanups::run_subtree(&Element::Window(win.clone()));
+                win.inner.layers.release();
                 win.remove();
                 win.close(&self.renderer_data());
             });
@@ -107,6 +151,16 @@ impl Backend {
         self.inner.windows.borrow_mut().remove(id)
     }
 
+    /// The clock animations of this thread run on. See [`AnimationClock::set_local`] to replace it.
+    pub fn clock(&self) -> AnimationClock {
+        AnimationClock::local()
+    }
+
+    /// While true, animations that aren't marked as essential jump to their end values, and transitions cross-fade.
+    pub fn reduced_motion(&self) -> Signal<bool> {
+        motion::reduced_motion()
+    }
+
     pub fn renderer_data(&self) -> Ref<RendererData> {
         self.inner.backend.renderer_data()
     }
@@ -124,6 +178,19 @@ impl Drop for BackendInner {
     }
 }
 
+/// Adds up the mouse wheel movement of a batch of events, which would otherwise be lost when they are deduplicated.
+fn sum_scrolls(events: Vec<WindowEvent>) -> impl DoubleEndedIterator<Item = WindowEvent> {
+    let (x, y) = events.iter().fold((0.0, 0.0), |(x, y), event| match event {
+        WindowEvent::MouseScroll(delta) => (x + delta.x, y + delta.y),
+        _ => (x, y)
+    });
+
+    events.into_iter().map(move |event| match event {
+        WindowEvent::MouseScroll(_) => WindowEvent::MouseScroll(Position::new(x, y)),
+        event => event
+    })
+}
+
 fn reverse_dedup_enums<T>(iter: impl DoubleEndedIterator<Item = T>) -> impl DoubleEndedIterator<Item = T> {
     let mut known = Vec::new();
     
@@ -5,7 +5,8 @@ use lumi2d::types::{BackendEvent, WindowId};
 pub enum CustomEvent {
     BackendEvent(BackendEvent),
     Callback(Box<dyn FnOnce() + Send + 'static>),
-    Redraw(WindowId)
+    Redraw(WindowId),
+    Timer(u64)
 }
 
 impl Debug for CustomEvent {
@@ -14,6 +15,7 @@ impl Debug for CustomEvent {
             CustomEvent::BackendEvent(_) => "BackendEvent",
             CustomEvent::Callback(_) => "Callback",
             CustomEvent::Redraw(_) => "Redraw",
+            CustomEvent::Timer(_) => "Timer",
         }).finish()
     }
 }
\ No newline at end of file
@@ -0,0 +1,44 @@
+use crate::widgets::widget_builder::WidgetBuilder;
+
+use super::element_builder::ElementBuilder;
+
+
+/// A reusable piece of UI. The implementing type holds the component's props,
+/// and building it adds its subtree to a parent.
+pub trait Component {
+    /// Signals the component exposes to whoever uses it, or `()` if there are none.
+    type Output;
+
+    fn build(self, parent: &ElementBuilder) -> Self::Output;
+}
+
+impl<F: FnOnce(&ElementBuilder) -> O, O> Component for F {
+    type Output = O;
+
+    fn build(self, parent: &ElementBuilder) -> O {
+        self(parent)
+    }
+}
+
+impl Component for ElementBuilder {
+    type Output = ElementBuilder;
+
+    fn build(self, parent: &ElementBuilder) -> ElementBuilder {
+        parent.child(self)
+    }
+}
+
+impl Component for WidgetBuilder {
+    type Output = ElementBuilder;
+
+    fn build(self, parent: &ElementBuilder) -> ElementBuilder {
+        parent.child_widget(self)
+    }
+}
+
+impl ElementBuilder {
+    /// Adds the subtree of `component` to this element and returns its outputs.
+    pub fn component<C: Component>(&self, component: C) -> C::Output {
+        component.build(self)
+    }
+}
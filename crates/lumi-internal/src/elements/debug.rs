@@ -0,0 +1,144 @@
+use std::fmt::Write;
+
+use crate::{signals::SignalTrait, widgets::{Widget, WidgetTrait}};
+
+use super::{element::{Element, ElementTrait}, query::Labels};
+
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TreeFormat {
+    /// One line per element, indented by depth.
+    #[default]
+    Text,
+    /// Nested objects, with the children of each in its `nodes` array.
+    Json
+}
+
+/// What the tree dump shows of a single element.
+#[derive(Debug)]
+struct DebugNode {
+    kind: &'static str,
+    identifier: u64,
+    /// Field names with their values, already formatted
+    fields: Vec<(&'static str, String)>,
+    children: Vec<DebugNode>
+}
+
+
+impl Element {
+    /// Describes this element and everything below it, with the current values of their signals.
+    pub fn debug_tree(&self, format: TreeFormat) -> String {
+        let node = DebugNode::new(self);
+        let mut out = String::new();
+
+        match format {
+            TreeFormat::Text => node.write_text(&mut out, 0),
+            TreeFormat::Json => node.write_json(&mut out)
+        }
+
+        out
+    }
+}
+
+impl DebugNode {
+    fn new(element: &Element) -> Self {
+        let mut fields = Vec::new();
+
+        let kind = match element {
+            Element::Root(_) => "Root",
+            Element::Window(_) => "Window",
+            Element::Dynamic(_) => "Dynamic",
+            Element::Portal(_) => "Portal",
+            Element::Group(group) => {
+                let transform = *group.transform().get();
+                fields.push(("translate", format!("({}, {})", transform.translate_x, transform.translate_y)));
+                fields.push(("scale", format!("({}, {})", transform.scale_x, transform.scale_y)));
+                if transform.is_rotated() {
+                    fields.push(("rotation", transform.rotation.to_string()));
+                }
+                fields.push(("opacity", transform.opacity.to_string()));
+                if let Some(clip) = group.clip() {
+                    let bounds = clip.get().bounds;
+                    fields.push(("clip", format!("({}, {}, {}x{})", bounds.x, bounds.y, bounds.width, bounds.height)));
+                }
+                "Group"
+            },
+            Element::Widget(widget) => {
+                let widget = widget.widget();
+                fields.push(("widget", match widget {
+                    Widget::Rectangle(_) => "Rectangle",
+                    Widget::Text(_) => "Text",
+                    Widget::Image(_) => "Image",
+                    Widget::Svg(_) => "Svg",
+                    Widget::Interact(_) => "Interact"
+                }.to_string()));
+
+                if let Some(bounds) = widget.bounds() {
+                    fields.push(("bounds", format!("({}, {}, {}x{})", bounds.x, bounds.y, bounds.width, bounds.height)));
+                }
+
+                match widget {
+                    Widget::Rectangle(rectangle) => fields.push(("color", format!("#{:08X}", *rectangle.color.get()))),
+                    Widget::Svg(svg) => fields.push(("color", format!("#{:08X}", *svg.color.get()))),
+                    Widget::Text(text) => fields.push(("text", format!("{:?}", text.text.get().as_ref()))),
+                    _ => {}
+                }
+                "Widget"
+            }
+        };
+
+        if let Some(labels) = Labels::of(element.identifier()) {
+            let labels = [("id", labels.id), ("name", labels.name), ("test_id", labels.test_id)];
+            fields.extend(labels.into_iter().filter_map(|(field, label)| Some((field, format!("{:?}", label?)))));
+        }
+
+        let children = element.children().read().unwrap().iter().map(DebugNode::new).collect::<Vec<_>>();
+        fields.push(("children", children.len().to_string()));
+
+        Self { kind, identifier: element.identifier(), fields, children }
+    }
+
+    fn write_text(&self, out: &mut String, depth: usize) {
+        let _ = write!(out, "{:indent$}{} {}", "", self.kind, self.identifier, indent = depth * 2);
+        for (field, value) in &self.fields {
+            let _ = write!(out, " {field}={value}");
+        }
+        out.push('\n');
+
+        for child in &self.children {
+            child.write_text(out, depth + 1);
+        }
+    }
+
+    fn write_json(&self, out: &mut String) {
+        let _ = write!(out, "{{\"kind\":\"{}\",\"identifier\":{}", self.kind, self.identifier);
+        for (field, value) in &self.fields {
+            let _ = write!(out, ",\"{field}\":\"{}\"", escape_json(value));
+        }
+
+        out.push_str(",\"nodes\":[");
+        for (index, child) in self.children.iter().enumerate() {
+            if index > 0 {
+                out.push(',');
+            }
+            child.write_json(out);
+        }
+        out.push_str("]}");
+    }
+}
+
+fn escape_json(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for char in value.chars() {
+        match char {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            char if char.is_control() => { let _ = write!(escaped, "\\u{:04x}", char as u32); },
+            char => escaped.push(char)
+        }
+    }
+
+    escaped
+}
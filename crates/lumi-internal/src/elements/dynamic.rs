@@ -1,11 +1,11 @@
-use std::{fmt::Debug, rc::Rc, sync::{Arc, RwLock, Weak}};
+use std::{fmt::Debug, panic::{self, AssertUnwindSafe}, rc::Rc, sync::{Arc, RwLock, Weak}};
 
 
 use clone_macro::clone;
 
-use crate::{backend::Backend, signals::{Signal, SignalTrait}};
+use crate::{animations::transition::{Transition, TransitionPhase, TransitionState}, backend::Backend, signals::{Signal, SignalTrait}};
 
-use super::{element::{Element, ElementRef, ElementRefTrait, ElementTrait}, element_builder::{ElementBuilder, ElementBuilderTrait}};
+use super::{element::{Element, ElementRef, ElementRefTrait, ElementTrait}, element_builder::{ElementBuilder, ElementBuilderTrait}, error_boundary::{BoundaryError, ErrorBoundary}, group::GroupElement, lifecycle::{Cleanups, Lifecycle}, suspense::Suspense};
 
 #[derive(Debug, Clone)]
 pub struct DynamicElement {
@@ -16,7 +16,14 @@ pub struct DynamicElement {
 pub struct DynamicElementInner {
     pub(crate) identifier: u64,
     pub(crate) parent: Option<ElementRef>,
-    pub(crate) children: RwLock<Vec<Element>>
+    pub(crate) children: RwLock<Vec<Element>>,
+    pub(crate) transition: Option<Transition>,
+    /// The identifier of the group holding the current children, if they are transitioned
+    pub(crate) entered: RwLock<Option<(u64, TransitionState)>>,
+    /// Registered with `on_cleanup` while building the current children, if they aren't transitioned
+    pub(crate) cleanups: RwLock<Cleanups>,
+    pub(crate) boundary: Option<ErrorBoundary>,
+    pub(crate) suspense: Option<Suspense>
 }
 
 pub type DynamicElementRef = Weak<DynamicElementInner>;
@@ -27,6 +34,23 @@ impl ElementRefTrait for DynamicElementRef {
     }
 }
 
+impl DynamicElement {
+    /// The error boundary this element shows the children or fallback of, if it is one.
+    pub(crate) fn boundary(&self) -> Option<&ErrorBoundary> {
+        self.inner.boundary.as_ref()
+    }
+
+    /// The suspense tracking the futures read by this element's children, if it is one.
+    pub(crate) fn suspense(&self) -> Option<&Suspense> {
+        self.inner.suspense.as_ref()
+    }
+
+    /// Takes the cleanups registered while building the current children, leaving none behind.
+    pub(crate) fn take_cleanups(&self) -> Cleanups {
+        std::mem::take(&mut *self.inner.cleanups.write().unwrap())
+    }
+}
+
 impl ElementTrait for DynamicElement {
     fn children(&self) -> &RwLock<Vec<Element>> {
         &self.inner.children
@@ -49,11 +73,24 @@ impl ElementTrait for DynamicElement {
     fn weak(&self) -> ElementRef {
         ElementRef::Dynamic(Arc::downgrade(&self.inner))
     }
+
+    fn destruct(self, backend: &Backend) {
+        Cleanups::run_attached(self.identifier());
+        self.take_cleanups().run();
+
+        for child in self.children().write().unwrap().drain(..) {
+            child.destruct(backend);
+        }
+    }
 }
 
 pub struct DynamicElementBuilder {
     callback: Box<dyn Fn(&Backend, Arc<DynamicElementInner>)>,
-    child_container: ChildBuilderContainer
+    child_container: ChildBuilderContainer,
+    transition: Option<Transition>,
+    boundary: Option<ErrorBoundary>,
+    suspense: Option<Suspense>,
+    lifecycle: Lifecycle
 }
 
 impl DynamicElementBuilder {
@@ -64,15 +101,20 @@ impl DynamicElementBuilder {
             callback: Box::new(move |backend, inner| {
                 let backend = backend.clone();
                 
-                let rebuild_cb = clone!([container, backend, inner], move |val: &T| {
+                let rebuild = clone!([container, backend, inner], move |val: &T| {
                     let child_container = ChildBuilderContainer::new();
 
                     let element_builder: ElementBuilder = child_container.clone().into();
 
-                    container(val, element_builder.clone());
+                    let ((), cleanups) = Cleanups::collect(|| container(val, element_builder.clone()));
 
                     let p = Element::Dynamic(DynamicElement { inner: inner.clone() }); 
 
+                    if let Some(transition) = &inner.transition {
+                        DynamicElement::transition_children(&backend, &inner, transition, &p, &child_container, cleanups);
+                        return;
+                    }
+
                     let new_children = child_container.build_children(&backend, Some(p.weak()));
                     
                     let mut children = inner.children.write().unwrap();
@@ -84,18 +126,106 @@ impl DynamicElementBuilder {
 
                     drop(children);
 
+                    let old_cleanups = std::mem::replace(&mut *inner.cleanups.write().unwrap(), cleanups);
+                    old_cleanups.run();
+
                     for ch in old_children {
                         // ch.remove();
                         ch.destruct(&backend);
                     }
                 });
 
+                // Panics are passed on to the closest error boundary, which replaces this element with its fallback.
+                // The current children stay until then, while anything built before the panic is dropped.
+                // Futures read while rebuilding count towards the closest suspense
+                let rebuild_cb = clone!([inner], move |val: &T| {
+                    let element = Element::Dynamic(DynamicElement { inner: inner.clone() });
+                    let tracked = || match Suspense::closest(&element) {
+                        Some(suspense) => suspense.track(|| rebuild(val)),
+                        None => rebuild(val)
+                    };
+
+                    let Err(payload) = panic::catch_unwind(AssertUnwindSafe(tracked)) else { return };
+
+                    match ErrorBoundary::closest(&element) {
+                        Some(boundary) => boundary.report(BoundaryError::from_panic(payload.as_ref())),
+                        None => panic::resume_unwind(payload)
+                    }
+                });
+
                 rebuild_cb(signal.get().as_ref());
 
                 signal.subscribe(rebuild_cb);
 
             }),
-            child_container: ChildBuilderContainer::new()
+            child_container: ChildBuilderContainer::new(),
+            transition: None,
+            boundary: None,
+            suspense: None,
+            lifecycle: Lifecycle::default()
+        }
+    }
+
+    /// Keeps replaced children around until their exit transition finished, and transitions new ones in.
+    pub fn with_transition(self, transition: Transition) -> Self {
+        Self {
+            transition: Some(transition),
+            ..self
+        }
+    }
+
+    pub(crate) fn with_boundary(self, boundary: ErrorBoundary) -> Self {
+        Self {
+            boundary: Some(boundary),
+            ..self
+        }
+    }
+
+    pub(crate) fn with_suspense(self, suspense: Suspense) -> Self {
+        Self {
+            suspense: Some(suspense),
+            ..self
+        }
+    }
+}
+
+impl DynamicElement {
+    fn transition_children(backend: &Backend, inner: &Arc<DynamicElementInner>, transition: &Transition, parent: &Element, child_container: &ChildBuilderContainer, cleanups: Cleanups) {
+        let previous = inner.entered.write().unwrap().take();
+        let appear = previous.is_some() || transition.appear;
+
+        let state = TransitionState::new(if appear { 0.0 } else { 1.0 });
+        let group = Element::Group(GroupElement::new(Some(parent.weak()), transition.transform(&state), None));
+
+        let new_children = child_container.build_children(backend, Some(group.weak()));
+        *group.children().write().unwrap() = new_children;
+        cleanups.attach(group.identifier());
+
+        if let Some(bounds) = group.bounds() {
+            state.pivot.set(bounds.center());
+        }
+
+        *inner.entered.write().unwrap() = Some((group.identifier(), state.clone()));
+        inner.children.write().unwrap().push(group);
+
+        if appear {
+            transition.start(&state, TransitionPhase::Entering, || {});
+        }
+
+        if let Some((identifier, previous)) = previous {
+            let weak = Arc::downgrade(inner);
+            let backend = backend.clone();
+
+            transition.start(&previous, TransitionPhase::Exiting, move || {
+                let Some(inner) = weak.upgrade() else { return };
+                let mut children = inner.children.write().unwrap();
+
+                if let Some(index) = children.iter().position(|child| child.identifier() == identifier) {
+                    let old = children.remove(index);
+                    drop(children);
+                    old.destruct(&backend);
+                }
+            });
         }
     }
 }
@@ -110,11 +240,23 @@ impl ElementBuilderTrait for Arc<DynamicElementBuilder> {
             identifier: fastrand::u64(..),
             parent: parent.clone(),
             children: RwLock::new(Vec::new()),
+            transition: self.transition.clone(),
+            entered: RwLock::new(None),
+            cleanups: RwLock::new(Cleanups::default()),
+            boundary: self.boundary.clone(),
+            suspense: self.suspense.clone()
         });
 
         (self.callback)(backend, dynamic.clone());
 
-        Element::Dynamic(DynamicElement { inner: dynamic })
+        let element = Element::Dynamic(DynamicElement { inner: dynamic });
+        self.lifecycle.mounted(&element);
+
+        element
+    }
+
+    fn lifecycle(&self) -> Option<&Lifecycle> {
+        Some(&self.lifecycle)
     }
 }
 
@@ -141,7 +283,7 @@ impl ElementBuilderTrait for ChildBuilderContainer {
 }
 
 impl ChildBuilderContainer {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         Self {
             children: Arc::new(RwLock::new(Vec::new()))
         }
@@ -3,9 +3,9 @@ use std::{fmt::Debug, sync::RwLock};
 use enum_dispatch::enum_dispatch;
 use log::error;
 
-use crate::{backend::Backend, widgets::{Widget, WidgetTrait}};
+use crate::{backend::Backend, geometry::{Bounds, Clip, Transform}, signals::{Signal, SignalTrait}, widgets::{Widget, WidgetTrait}};
 
-use super::{dynamic::*, root::*, widget::*, window::*};
+use super::{dynamic::*, group::*, lifecycle::Cleanups, portal::*, root::*, widget::*, window::*};
 
 
 
@@ -15,7 +15,9 @@ pub enum Element {
     Root(RootElement),
     Widget(WidgetElement),
     Window(Window),
-    Dynamic(DynamicElement)
+    Dynamic(DynamicElement),
+    Group(GroupElement),
+    Portal(PortalElement)
 }
 
 #[enum_dispatch(ElementRefTrait)]
@@ -24,7 +26,9 @@ pub enum ElementRef {
     Root(RootElementRef),
     Widget(WidgetElementRef),
     Window(WindowRef),
-    Dynamic(DynamicElementRef)
+    Dynamic(DynamicElementRef),
+    Group(GroupElementRef),
+    Portal(PortalElementRef)
 }
 
 #[enum_dispatch]
@@ -40,6 +44,8 @@ pub trait ElementTrait {
     fn render_into(&self, objects: &mut Vec<Element>);
     fn weak(&self) -> ElementRef;
     fn destruct(self, backend: &Backend) where Self: Sized {
+        Cleanups::run_attached(self.identifier());
+
         for child in self.children().write().unwrap().drain(..) {
             child.destruct(backend);
         }
@@ -61,12 +67,13 @@ pub trait ElementTrait {
 }
 
 impl Element {
-    pub(crate) fn new_widget(parent: Option<ElementRef>, children: Vec<Element>, widget: Widget) -> Self {
+    pub(crate) fn new_widget(parent: Option<ElementRef>, children: Vec<Element>, widget: Widget, z_index: Option<Signal<i32>>) -> Self {
         Self::Widget(
             WidgetElement::new(
                 parent,
                 widget,
-                RwLock::new(children)
+                RwLock::new(children),
+                z_index
             )
         )
     }
@@ -89,7 +96,8 @@ impl Element {
         let element = Self::new_widget(
             Some(self.weak()),
             Vec::with_capacity(widget.expected_children()),
-            widget
+            widget,
+            None
         );
 
         self.children().write().unwrap().push(element.clone());
@@ -100,10 +108,125 @@ impl Element {
     pub fn get_window(&self) -> Option<Window> {
         if let Element::Window(window) = &self {
             Some(window.clone()) //window.clone().upgrade().map(|inner| Window { inner })
+        } else if let Element::Portal(portal) = &self {
+            portal.window()
         } else {
             self.parent().as_ref()?
             .upgrade_element()?
             .get_window()
         }
     }
+
+    /// The combined transform of this element and all groups above it, up to the window or portal.
+    pub fn get_transform(&self) -> Signal<Transform> {
+        if let Element::Window(_) | Element::Portal(_) = self {
+            return Signal::constant(Transform::IDENTITY);
+        }
+
+        let parent = self.parent().as_ref()
+        .and_then(|p| p.upgrade_element())
+        .map(|p| p.get_transform());
+
+        let own = if let Element::Group(group) = self {
+            Some(group.transform().clone())
+        } else { None };
+
+        match (parent, own) {
+            (Some(parent), Some(own)) => (parent, own).relative(|(parent, own)| parent.compose(own)),
+            (Some(parent), None) => parent,
+            (None, Some(own)) => own,
+            (None, None) => Signal::constant(Transform::IDENTITY)
+        }
+    }
+
+    /// The intersection of the clips of all groups above this element, up to the window or portal.
+    pub fn get_clip(&self) -> Signal<Option<Clip>> {
+        if let Element::Window(_) | Element::Portal(_) = self {
+            return Signal::constant(None);
+        }
+
+        let parent = self.parent().as_ref().and_then(|p| p.upgrade_element());
+        let parent_clip = parent.as_ref().map_or_else(|| Signal::constant(None), |p| p.get_clip());
+
+        let own = if let Element::Group(group) = self {
+            group.clip().cloned()
+        } else { None };
+
+        let Some(own) = own else {
+            return parent_clip;
+        };
+
+        // The group's own clip is positioned in its parent's space
+        let parent_transform = parent.as_ref().map_or_else(|| Signal::constant(Transform::IDENTITY), |p| p.get_transform());
+
+        (own, parent_transform, parent_clip).relative(|(own, transform, parent_clip)| {
+            let own = transform.apply_clip(own);
+
+            Some(match parent_clip.as_ref() {
+                Some(parent_clip) => own.intersect(parent_clip),
+                None => own
+            })
+        })
+    }
+
+    /// The area widgets anchored to this element are positioned in:
+    /// a widget's own bounds, the size of a window, or the area of a group's clip (or else of its parent).
+    /// Other elements pass on the area of their parent.
+    pub fn get_anchor_bounds(&self) -> Signal<Bounds> {
+        match self {
+            Element::Window(window) => return window.innerest().state.dimensions.relative(|dims| {
+                Bounds::new(0, 0, dims.width, dims.height)
+            }),
+            Element::Widget(widget) => return widget.widget().bounds_signal().clone(),
+            // The area is in the parent's space, while the children are laid out before the group's transform
+            Element::Group(group) => {
+                let area = match group.clip() {
+                    Some(clip) => clip.relative(|clip| clip.bounds),
+                    None => self.parent_anchor_bounds()
+                };
+
+                return (area, group.transform().clone()).relative(|(area, transform)| {
+                    transform.invert_bounds(**area).unwrap_or_default()
+                });
+            },
+            _ => {}
+        }
+
+        self.parent_anchor_bounds()
+    }
+
+    fn parent_anchor_bounds(&self) -> Signal<Bounds> {
+        self.parent().as_ref()
+        .and_then(|p| p.upgrade_element())
+        .map_or_else(|| Signal::constant(Bounds::default()), |p| p.get_anchor_bounds())
+    }
+
+    /// The z-index of the closest widget element at or above this one that has one set, up to the window or portal.
+    pub fn z_index(&self) -> i32 {
+        match self {
+            Element::Widget(widget) => if let Some(z_index) = widget.z_index() {
+                return *z_index.get();
+            },
+            Element::Window(_) | Element::Portal(_) => return 0,
+            _ => {}
+        }
+
+        self.parent().as_ref()
+        .and_then(|p| p.upgrade_element())
+        .map_or(0, |p| p.z_index())
+    }
+
+    /// The untransformed area covered by all widgets in this subtree, not including other windows.
+    pub fn bounds(&self) -> Option<Bounds> {
+        let mut elements = Vec::new();
+        self.render_into(&mut elements);
+
+        elements.iter()
+        .filter_map(|element| {
+            if let Element::Widget(widget_element) = element {
+                widget_element.widget().bounds()
+            } else { None }
+        })
+        .reduce(Bounds::union)
+    }
 }
\ No newline at end of file
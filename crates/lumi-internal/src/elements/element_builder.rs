@@ -4,7 +4,7 @@ use enum_dispatch::enum_dispatch;
 
 use crate::{backend::Backend, elements::element::Element, widgets::widget_builder::{WidgetBuilder, WidgetBuilderTrait}};
 
-use super::{dynamic::*, element::ElementRef, root::*, widget::*};
+use super::{dynamic::*, element::ElementRef, group::*, lifecycle::Lifecycle, portal::*, root::*, widget::*};
 
 #[enum_dispatch(ElementBuilderTrait)]
 #[derive(Debug, Clone)]
@@ -12,6 +12,8 @@ pub enum ElementBuilder {
     Root(Arc<RootElementBuilder>),
     Widget(Arc<WidgetElementBuilder>),
     Dynamic(Arc<DynamicElementBuilder>),
+    Portal(Arc<PortalElementBuilder>),
+    Group(Arc<GroupElementBuilder>),
     ChildContainer(ChildBuilderContainer)
 }
 
@@ -20,6 +22,10 @@ pub enum ElementBuilder {
 pub trait ElementBuilderTrait {
     fn children(&self) -> &RwLock<Vec<ElementBuilder>>;
     fn build(&self, backend: &Backend, parent: Option<ElementRef>) -> Element;
+    /// The mount and unmount callbacks of this builder, if it builds elements itself.
+    fn lifecycle(&self) -> Option<&Lifecycle> {
+        None
+    }
 }
 
 
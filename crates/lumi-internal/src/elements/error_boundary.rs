@@ -0,0 +1,106 @@
+use std::{any::Any, fmt::Display, sync::Arc, time::Duration};
+
+use log::error;
+
+use crate::{signals::{Signal, SignalTrait}, timer::set_timeout};
+
+use super::{dynamic::{ChildBuilderContainer, DynamicElementBuilder}, element::{Element, ElementRefTrait, ElementTrait}, element_builder::{ElementBuilder, ElementBuilderTrait}};
+
+
+/// What made an error boundary show its fallback.
+#[derive(Debug, Clone)]
+pub enum BoundaryError {
+    /// Building the children or rebuilding a dynamic element inside them panicked, with the panic message.
+    Panic(String),
+    /// An image or svg failed to load its source.
+    Load(String)
+}
+
+/// Catches panics while its children are built or rebuilt, as well as failed loads of their images and svgs,
+/// and shows a fallback instead of the children until it is reset.
+#[derive(Debug, Clone, Default)]
+pub struct ErrorBoundary {
+    error: Signal<Option<BoundaryError>>
+}
+
+
+impl ErrorBoundary {
+    /// The error the fallback is currently shown for.
+    pub fn error(&self) -> &Signal<Option<BoundaryError>> {
+        &self.error
+    }
+
+    /// Rebuilds the children instead of the fallback, to retry after an error.
+    pub fn reset(&self) {
+        self.error.set(None);
+    }
+
+    /// Shows the fallback for `error`, unless it is already shown.
+    pub(crate) fn report(&self, error: BoundaryError) {
+        error!("An error boundary caught an error: {error}");
+
+        // Errors are usually reported in the middle of building, so the children are only replaced afterwards
+        let signal = self.error.clone();
+        set_timeout(Duration::ZERO, move || if signal.get().is_none() {
+            signal.set(Some(error));
+        });
+    }
+
+    /// The closest boundary at or above `element` which still shows its children.
+    /// Errors of a fallback are caught by the next boundary above it this way.
+    pub(crate) fn closest(element: &Element) -> Option<ErrorBoundary> {
+        let own = match element {
+            Element::Dynamic(dynamic) => dynamic.boundary().filter(|b| b.error.get().is_none()).cloned(),
+            _ => None
+        };
+
+        own.or_else(|| {
+            let parent = element.parent().as_ref()?.upgrade_element()?;
+            Self::closest(&parent)
+        })
+    }
+}
+
+impl BoundaryError {
+    pub(crate) fn from_panic(payload: &(dyn Any + Send)) -> Self {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "Unknown panic".to_string()
+        };
+
+        Self::Panic(message)
+    }
+}
+
+impl Display for BoundaryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BoundaryError::Panic(message) => write!(f, "Panicked: {message}"),
+            BoundaryError::Load(message) => write!(f, "Failed to load: {message}")
+        }
+    }
+}
+
+
+impl ElementBuilder {
+    /// Adds an error boundary, and returns the builder to add the children it protects to.
+    /// `fallback` builds what is shown instead of them after an error, until `boundary` is reset.
+    pub fn error_boundary(&self, boundary: ErrorBoundary, fallback: impl Fn(&BoundaryError, ElementBuilder) + 'static) -> Self {
+        let content = ChildBuilderContainer::new();
+        let children = content.clone();
+
+        let dynamic = DynamicElementBuilder::new(boundary.error.clone(), move |error, parent| match error {
+            None => for child in children.children().read().unwrap().iter() {
+                parent.child(child.clone());
+            },
+            Some(error) => fallback(error, parent)
+        }).with_boundary(boundary);
+
+        self.child(ElementBuilder::from(Arc::new(dynamic)));
+
+        content.into()
+    }
+}
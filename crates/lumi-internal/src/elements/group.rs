@@ -0,0 +1,173 @@
+use std::sync::{Arc, RwLock, Weak};
+
+use crate::{backend::Backend, geometry::{Clip, Transform}, signals::{Signal, SignalTrait}};
+
+use super::{element::{Element, ElementRef, ElementRefTrait, ElementTrait}, element_builder::{ElementBuilder, ElementBuilderTrait}, lifecycle::Lifecycle};
+
+
+/// An element that applies a transform and clip to everything drawn by its descendants.
+#[derive(Debug, Clone)]
+pub struct GroupElement {
+    inner: Arc<GroupElementInner>
+}
+
+#[derive(Debug)]
+pub struct GroupElementInner {
+    pub(crate) identifier: u64,
+    pub(crate) parent: Option<ElementRef>,
+    pub(crate) children: RwLock<Vec<Element>>,
+    pub(crate) transform: Signal<Transform>,
+    /// Positioned in the parent's space, so the group's own transform doesn't move it
+    pub(crate) clip: Option<Signal<Clip>>
+}
+
+pub type GroupElementRef = Weak<GroupElementInner>;
+
+impl GroupElement {
+    pub fn new(parent: Option<ElementRef>, transform: Signal<Transform>, clip: Option<Signal<Clip>>) -> Self {
+        Self {
+            inner: Arc::new(GroupElementInner {
+                identifier: fastrand::u64(..),
+                parent,
+                children: RwLock::new(Vec::new()),
+                transform,
+                clip
+            })
+        }
+    }
+
+    pub fn transform(&self) -> &Signal<Transform> {
+        &self.inner.transform
+    }
+
+    pub fn clip(&self) -> Option<&Signal<Clip>> {
+        self.inner.clip.as_ref()
+    }
+}
+
+impl ElementRefTrait for GroupElementRef {
+    fn upgrade_element(&self) -> Option<Element> {
+        self.upgrade().map(|inner| GroupElement { inner }.into())
+    }
+}
+
+impl ElementTrait for GroupElement {
+    fn children(&self) -> &RwLock<Vec<Element>> {
+        &self.inner.children
+    }
+
+    fn parent(&self) -> &Option<ElementRef> {
+        &self.inner.parent
+    }
+
+    fn identifier(&self) -> u64 {
+        self.inner.identifier
+    }
+
+    fn render_into(&self, objects: &mut Vec<Element>) {
+        for child in self.children().read().unwrap().iter() {
+            child.render_into(objects)
+        }
+    }
+
+    fn weak(&self) -> ElementRef {
+        ElementRef::Group(Arc::downgrade(&self.inner))
+    }
+}
+
+
+/// Positions, scales, rotates and fades a subtree as a unit.
+/// Its children are positioned relative to `(x, y)` instead of the window, and `Interact` widgets inside it
+/// get hit tested and report drag positions in that space as well.
+#[derive(Debug, Clone)]
+pub struct GroupBuilder {
+    pub x: Signal<f32>,
+    pub y: Signal<f32>,
+    pub scale_x: Signal<f32>,
+    pub scale_y: Signal<f32>,
+    /// Clockwise, in radians.
+    pub rotation: Signal<f32>,
+    /// The point the group is scaled and rotated around, relative to `(x, y)`.
+    pub origin: Signal<(f32, f32)>,
+    /// Multiplied with the alpha of every color drawn by the children.
+    pub opacity: Signal<f32>
+}
+
+#[derive(Debug)]
+pub struct GroupElementBuilder {
+    transform: Signal<Transform>,
+    clip: Option<Signal<Clip>>,
+    children: RwLock<Vec<ElementBuilder>>,
+    lifecycle: Lifecycle
+}
+
+impl GroupElementBuilder {
+    pub(crate) fn new(transform: Signal<Transform>, clip: Option<Signal<Clip>>) -> Self {
+        Self {
+            transform,
+            clip,
+            children: RwLock::new(Vec::new()),
+            lifecycle: Lifecycle::default()
+        }
+    }
+}
+
+impl GroupBuilder {
+    pub fn transform(&self) -> Signal<Transform> {
+        (self.x.clone(), self.y.clone(), self.scale_x.clone(), self.scale_y.clone(), self.rotation.clone(), self.origin.clone(), self.opacity.clone())
+        .relative(|(x, y, scale_x, scale_y, rotation, origin, opacity)| Transform {
+            opacity: **opacity,
+            ..Transform::translate(**x, **y)
+            .compose(&Transform::rotate_around(**rotation, origin.0, origin.1))
+            .compose(&Transform::scale_around(**scale_x, **scale_y, origin.0, origin.1))
+        })
+    }
+}
+
+impl Default for GroupBuilder {
+    fn default() -> Self {
+        Self {
+            x: Signal::constant(0.0),
+            y: Signal::constant(0.0),
+            scale_x: Signal::constant(1.0),
+            scale_y: Signal::constant(1.0),
+            rotation: Signal::constant(0.0),
+            origin: Signal::constant((0.0, 0.0)),
+            opacity: Signal::constant(1.0)
+        }
+    }
+}
+
+impl ElementBuilder {
+    /// Adds a group, and returns the builder to add its children to.
+    pub fn group(&self, group: GroupBuilder) -> Self {
+        self.child(ElementBuilder::Group(Arc::new(GroupElementBuilder::new(group.transform(), None))))
+    }
+}
+
+impl ElementBuilderTrait for Arc<GroupElementBuilder> {
+    fn children(&self) -> &RwLock<Vec<ElementBuilder>> {
+        &self.children
+    }
+
+    fn build(&self, backend: &Backend, parent: Option<ElementRef>) -> Element {
+        let element = Element::Group(GroupElement::new(parent, self.transform.clone(), self.clip.clone()));
+
+        // The RwLockWriteGuard needs to be dropped before we can return the element
+        {
+            let mut new_children = element.children().write().unwrap();
+
+            *new_children = self.children.read().unwrap().iter()
+            .map(|child| child.build(backend, Some(element.weak())))
+            .collect();
+        }
+
+        self.lifecycle.mounted(&element);
+
+        element
+    }
+
+    fn lifecycle(&self) -> Option<&Lifecycle> {
+        Some(&self.lifecycle)
+    }
+}
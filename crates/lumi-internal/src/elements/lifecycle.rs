@@ -0,0 +1,173 @@
+use std::{fmt::Debug, panic::{self, AssertUnwindSafe}, rc::Rc, sync::RwLock};
+
+use log::{debug, warn};
+
+use super::{element::{Element, ElementRefTrait, ElementTrait}, element_builder::{ElementBuilder, ElementBuilderTrait}, query::Labels};
+
+
+/// Callbacks run when the elements built from a builder are mounted or unmounted,
+/// and the labels they are indexed with while mounted.
+#[derive(Default)]
+pub struct Lifecycle {
+    mount: RwLock<Vec<Rc<dyn Fn(&Element)>>>,
+    unmount: RwLock<Vec<Rc<dyn Fn(&Element)>>>,
+    pub(crate) labels: RwLock<Labels>
+}
+
+/// Functions to run once the elements they belong to are destructed.
+#[derive(Default)]
+pub(crate) struct Cleanups(Vec<Box<dyn FnOnce()>>);
+
+
+/// Registers `cleanup` to run when the elements currently being built are unmounted.
+/// Works inside dynamic element callbacks (running when their children are replaced) and mount callbacks.
+pub fn on_cleanup(cleanup: impl FnOnce() + 'static) {
+    let registered = crate::LOCAL_CLEANUP_SCOPES.with(|scopes| {
+        if let Some(scope) = scopes.borrow_mut().last_mut() {
+            scope.0.push(Box::new(cleanup));
+            true
+        } else { false }
+    });
+
+    if !registered {
+        debug!("on_cleanup was called outside of a dynamic element or mount callback, it will never run.");
+    }
+}
+
+
+impl Lifecycle {
+    /// Runs the mount callbacks for `element`, and keeps its unmount callbacks until it is destructed.
+    pub(crate) fn mounted(&self, element: &Element) {
+        let labels = self.labels.read().unwrap().clone();
+        if !labels.is_empty() {
+            labels.index(element);
+        }
+
+        let mount = self.mount.read().unwrap().clone();
+        let ((), mut cleanups) = Cleanups::collect(|| {
+            for callback in mount {
+                callback(element);
+            }
+        });
+
+        for callback in self.unmount.read().unwrap().iter().cloned() {
+            let weak = element.weak();
+
+            cleanups.0.push(Box::new(move || {
+                if let Some(element) = weak.upgrade_element() {
+                    callback(&element);
+                }
+            }));
+        }
+
+        if !self.labels.read().unwrap().is_empty() {
+            let identifier = element.identifier();
+            cleanups.0.push(Box::new(move || Labels::unindex(identifier)));
+        }
+
+        cleanups.attach(element.identifier());
+    }
+}
+
+impl Debug for Lifecycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Lifecycle")
+        .field("mount", &self.mount.read().unwrap().len())
+        .field("unmount", &self.unmount.read().unwrap().len())
+        .field("labels", &self.labels.read().unwrap())
+        .finish()
+    }
+}
+
+
+impl Cleanups {
+    /// Calls `f`, collecting everything it registers with [`on_cleanup`].
+    /// If `f` panics, the cleanups it registered so far are run right away, as nothing will be mounted.
+    pub(crate) fn collect<R>(f: impl FnOnce() -> R) -> (R, Cleanups) {
+        crate::LOCAL_CLEANUP_SCOPES.with(|scopes| scopes.borrow_mut().push(Cleanups::default()));
+        let result = panic::catch_unwind(AssertUnwindSafe(f));
+        let cleanups = crate::LOCAL_CLEANUP_SCOPES.with(|scopes| scopes.borrow_mut().pop()).unwrap_or_default();
+
+        match result {
+            Ok(result) => (result, cleanups),
+            Err(payload) => {
+                cleanups.run();
+                panic::resume_unwind(payload)
+            }
+        }
+    }
+
+    /// Keeps these cleanups until the element with the given identifier is destructed.
+    pub(crate) fn attach(self, identifier: u64) {
+        if self.0.is_empty() {
+            return;
+        }
+
+        crate::LOCAL_CLEANUPS.with(|cleanups| {
+            cleanups.borrow_mut().entry(identifier).or_default().0.extend(self.0);
+        });
+    }
+
+    /// Runs the cleanups attached to the element with the given identifier.
+    pub(crate) fn run_attached(identifier: u64) {
+        let attached = crate::LOCAL_CLEANUPS.with(|cleanups| cleanups.borrow_mut().remove(&identifier));
+
+        if let Some(attached) = attached {
+            attached.run();
+        }
+    }
+
+    /// Runs the cleanups of `element` and all of its descendants, for elements which are dropped without being destructed.
+    pub(crate) fn run_subtree(element: &Element) {
+        Self::run_attached(element.identifier());
+
+        // Dynamic elements keep the cleanups of their current children themselves
+        if let Element::Dynamic(dynamic) = element {
+            dynamic.take_cleanups().run();
+        }
+
+        for child in element.children().read().unwrap().iter() {
+            Self::run_subtree(child);
+        }
+    }
+
+    pub(crate) fn run(self) {
+        for cleanup in self.0 {
+            cleanup();
+        }
+    }
+}
+
+impl Debug for Cleanups {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Cleanups")
+        .field(&self.0.len())
+        .finish()
+    }
+}
+
+
+impl ElementBuilder {
+    /// Calls `callback` every time an element is built from this builder, once its children are built as well.
+    /// Its parent is already known, so [`Element::get_window`] works from here.
+    pub fn on_mount(&self, callback: impl Fn(&Element) + 'static) -> Self {
+        if let Some(lifecycle) = self.lifecycle() {
+            lifecycle.mount.write().unwrap().push(Rc::new(callback));
+        } else {
+            warn!("Tried to add an on_mount callback to a builder that doesn't build an element!");
+        }
+
+        self.clone()
+    }
+
+    /// Calls `callback` right before an element built from this builder is destructed, or its window is closed.
+    pub fn on_unmount(&self, callback: impl Fn(&Element) + 'static) -> Self {
+        if let Some(lifecycle) = self.lifecycle() {
+            lifecycle.unmount.write().unwrap().push(Rc::new(callback));
+        } else {
+            warn!("Tried to add an on_unmount callback to a builder that doesn't build an element!");
+        }
+
+        self.clone()
+    }
+}
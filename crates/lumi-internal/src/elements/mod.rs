@@ -3,4 +3,14 @@ pub mod element_builder;
 pub mod root;
 pub mod widget;
 pub mod window;
-pub mod dynamic;
\ No newline at end of file
+pub mod dynamic;
+pub mod group;
+pub mod component;
+pub mod lifecycle;
+pub mod portal;
+pub mod scroll;
+pub mod error_boundary;
+pub mod suspense;
+pub mod query;
+pub mod debug;
+pub mod responsive;
\ No newline at end of file
@@ -0,0 +1,258 @@
+use std::sync::{Arc, RwLock, Weak};
+
+use log::warn;
+use lumi2d::types::Event;
+
+use crate::{backend::Backend, custom_event::CustomEvent, signals::{Signal, SignalTrait}};
+
+use super::{element::{Element, ElementRef, ElementRefTrait, ElementTrait}, element_builder::{ElementBuilder, ElementBuilderTrait}, lifecycle::{Cleanups, Lifecycle}, window::{Window, WindowRef}};
+
+
+/// An element that belongs to its parent (for context and lifecycle),
+/// but draws its children into an overlay layer, possibly of another window.
+#[derive(Debug, Clone)]
+pub struct PortalElement {
+    inner: Arc<PortalElementInner>
+}
+
+#[derive(Debug)]
+pub struct PortalElementInner {
+    pub(crate) identifier: u64,
+    pub(crate) parent: Option<ElementRef>,
+    pub(crate) children: RwLock<Vec<Element>>,
+    /// The window the children are currently drawn into
+    pub(crate) window: RwLock<Option<WindowRef>>,
+    pub(crate) layer: String
+}
+
+pub type PortalElementRef = Weak<PortalElementInner>;
+
+#[derive(Debug, Clone)]
+pub enum PortalTarget {
+    /// A named layer of the portal's own window, drawn above the window's regular children.
+    Layer(String),
+    /// The window containing the given element, whenever there is one.
+    /// Its children are rebuilt every time the signal changes.
+    Window(Signal<Option<ElementRef>>)
+}
+
+#[derive(Debug)]
+pub struct PortalElementBuilder {
+    target: PortalTarget,
+    children: RwLock<Vec<ElementBuilder>>,
+    lifecycle: Lifecycle
+}
+
+/// The overlay layers of a window, drawn in the order they were first used.
+#[derive(Debug, Default)]
+pub(crate) struct Layers {
+    layers: RwLock<Vec<(String, Vec<PortalElementRef>)>>
+}
+
+
+impl PortalElement {
+    /// The window the children of this portal are drawn into.
+    pub fn window(&self) -> Option<Window> {
+        self.inner.window.read().unwrap().as_ref()?
+        .upgrade()
+        .map(|inner| Window { inner })
+    }
+
+    /// Adds the children of this portal to `objects`, as the window it targets draws them.
+    pub(crate) fn render_content(&self, objects: &mut Vec<Element>) {
+        for child in self.children().read().unwrap().iter() {
+            child.render_into(objects)
+        }
+    }
+
+    fn detach(&self, backend: &Backend) {
+        if let Some(window) = self.window() {
+            window.inner.layers.remove(&self.inner);
+            crate::global_send(Event::Custom(CustomEvent::Redraw(window.id())));
+        }
+        *self.inner.window.write().unwrap() = None;
+
+        for child in self.children().write().unwrap().drain(..) {
+            child.destruct(backend);
+        }
+    }
+
+    /// Drops the children along with the window they are drawn into, which is being closed.
+    /// The portal itself stays in its own tree, and builds them again once its target changes.
+    fn release(&self) {
+        *self.inner.window.write().unwrap() = None;
+
+        let children = std::mem::take(&mut *self.children().write().unwrap());
+        for child in children.iter() {
+            Cleanups::run_subtree(child);
+        }
+    }
+
+    fn attach(&self, backend: &Backend, window: Window, builders: &[ElementBuilder]) {
+        *self.inner.window.write().unwrap() = Some(window.weak());
+
+        let children = builders.iter()
+        .map(|child| child.build(backend, Some(self.weak())))
+        .collect();
+        *self.children().write().unwrap() = children;
+
+        window.inner.layers.add(&self.inner.layer, Arc::downgrade(&self.inner));
+        crate::global_send(Event::Custom(CustomEvent::Redraw(window.id())));
+    }
+}
+
+impl ElementRefTrait for PortalElementRef {
+    fn upgrade_element(&self) -> Option<Element> {
+        self.upgrade().map(|inner| PortalElement { inner }.into())
+    }
+}
+
+impl ElementTrait for PortalElement {
+    fn children(&self) -> &RwLock<Vec<Element>> {
+        &self.inner.children
+    }
+
+    fn parent(&self) -> &Option<ElementRef> {
+        &self.inner.parent
+    }
+
+    fn identifier(&self) -> u64 {
+        self.inner.identifier
+    }
+
+    fn render_into(&self, _: &mut Vec<Element>) {
+        // Drawn by the target window instead, see render_content
+    }
+
+    fn weak(&self) -> ElementRef {
+        ElementRef::Portal(Arc::downgrade(&self.inner))
+    }
+
+    fn destruct(self, backend: &Backend) {
+        Cleanups::run_attached(self.identifier());
+        self.detach(backend);
+    }
+}
+
+
+impl ElementBuilderTrait for Arc<PortalElementBuilder> {
+    fn children(&self) -> &RwLock<Vec<ElementBuilder>> {
+        &self.children
+    }
+
+    fn build(&self, backend: &Backend, parent: Option<ElementRef>) -> Element {
+        let (layer, window) = match &self.target {
+            PortalTarget::Layer(name) => (name.clone(), None),
+            PortalTarget::Window(window) => (String::new(), Some(window.clone()))
+        };
+
+        let portal = PortalElement {
+            inner: Arc::new(PortalElementInner {
+                identifier: fastrand::u64(..),
+                parent: parent.clone(),
+                children: RwLock::new(Vec::new()),
+                window: RwLock::new(None),
+                layer
+            })
+        };
+
+        match window {
+            None => {
+                let own_window = parent.and_then(|p| p.upgrade_element()).and_then(|p| p.get_window());
+
+                if let Some(own_window) = own_window {
+                    portal.attach(backend, own_window, &self.children.read().unwrap());
+                } else {
+                    warn!("Tried to build a portal into a layer outside of any window!");
+                }
+            },
+            Some(window) => {
+                let (weak, builder, backend) = (Arc::downgrade(&portal.inner), self.clone(), backend.clone());
+
+                let retarget = move |target: &Option<ElementRef>| {
+                    let Some(inner) = weak.upgrade() else { return };
+                    let portal = PortalElement { inner };
+
+                    portal.detach(&backend);
+
+                    let target = target.as_ref().and_then(|t| t.upgrade_element()).and_then(|t| t.get_window());
+                    if let Some(target) = target {
+                        portal.attach(&backend, target, &builder.children.read().unwrap());
+                    }
+                };
+
+                retarget(window.get().as_ref());
+                window.subscribe(retarget);
+            }
+        }
+
+        let element = Element::Portal(portal);
+        self.lifecycle.mounted(&element);
+
+        element
+    }
+
+    fn lifecycle(&self) -> Option<&Lifecycle> {
+        Some(&self.lifecycle)
+    }
+}
+
+impl PortalElementBuilder {
+    pub fn new(target: PortalTarget) -> Self {
+        Self {
+            target,
+            children: RwLock::new(Vec::new()),
+            lifecycle: Lifecycle::default()
+        }
+    }
+}
+
+impl ElementBuilder {
+    /// Adds a portal, which draws the children added to the returned builder into `target` instead of this element's place.
+    pub fn portal(&self, target: PortalTarget) -> Self {
+        self.child(ElementBuilder::Portal(Arc::new(PortalElementBuilder::new(target))))
+    }
+}
+
+
+impl Layers {
+    fn add(&self, name: &str, portal: PortalElementRef) {
+        let mut layers = self.layers.write().unwrap();
+
+        if let Some((_, portals)) = layers.iter_mut().find(|(layer, _)| layer == name) {
+            portals.push(portal);
+        } else if name.is_empty() {
+            // The default layer of portals into other windows stays below the named ones
+            layers.insert(0, (String::new(), vec![portal]));
+        } else {
+            layers.push((name.to_string(), vec![portal]));
+        }
+    }
+
+    fn remove(&self, portal: &Arc<PortalElementInner>) {
+        for (_, portals) in self.layers.write().unwrap().iter_mut() {
+            portals.retain(|p| p.strong_count() > 0 && !std::ptr::eq(p.as_ptr(), Arc::as_ptr(portal)));
+        }
+    }
+
+    /// Releases the children of every portal drawing into this window, as it is being closed.
+    /// Portals into another window aren't descendants of it, so their cleanups wouldn't run otherwise.
+    pub(crate) fn release(&self) {
+        let layers = std::mem::take(&mut *self.layers.write().unwrap());
+
+        for (_, portals) in layers {
+            for portal in portals.iter().filter_map(|p| p.upgrade()) {
+                PortalElement { inner: portal }.release();
+            }
+        }
+    }
+
+    /// Adds the contents of all layers to `objects`, from the bottom to the top layer.
+    pub(crate) fn render_into(&self, objects: &mut Vec<Element>) {
+        for (_, portals) in self.layers.read().unwrap().iter() {
+            for portal in portals.iter().filter_map(|p| p.upgrade()) {
+                PortalElement { inner: portal }.render_content(objects);
+            }
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+use log::warn;
+
+use super::{element::{Element, ElementRef, ElementRefTrait, ElementTrait}, element_builder::{ElementBuilder, ElementBuilderTrait}, window::Window};
+
+
+/// Names given to the elements built from a builder, to find them again in the built tree.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Labels {
+    /// Should be unique among the mounted elements.
+    pub id: Option<String>,
+    pub name: Option<String>,
+    /// Like `name`, but meant to be used by tests only.
+    pub test_id: Option<String>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Selector {
+    Id(String),
+    Name(String),
+    TestId(String)
+}
+
+/// The labels of the mounted elements, and the elements each selector matches, so queries don't walk the tree.
+#[derive(Debug, Default)]
+pub(crate) struct LabelIndex {
+    labels: HashMap<u64, (Labels, ElementRef)>,
+    selectors: HashMap<Selector, Vec<u64>>
+}
+
+
+impl Labels {
+    pub fn is_empty(&self) -> bool {
+        self.id.is_none() && self.name.is_none() && self.test_id.is_none()
+    }
+
+    /// The labels of the mounted element with the given identifier.
+    pub fn of(identifier: u64) -> Option<Labels> {
+        crate::LOCAL_LABELS.with(|index| index.borrow().labels.get(&identifier).map(|(labels, _)| labels.clone()))
+    }
+
+    /// Adds `element` to the index, until `unindex` is called with its identifier.
+    pub(crate) fn index(self, element: &Element) {
+        let identifier = element.identifier();
+
+        crate::LOCAL_LABELS.with(|index| {
+            let mut index = index.borrow_mut();
+
+            if let Some(id) = &self.id {
+                if index.selectors.contains_key(&Selector::Id(id.clone())) {
+                    warn!("The id {id} is used by more than one mounted element!");
+                }
+            }
+
+            for selector in self.selectors() {
+                index.selectors.entry(selector).or_default().push(identifier);
+            }
+
+            index.labels.insert(identifier, (self, element.weak()));
+        });
+    }
+
+    pub(crate) fn unindex(identifier: u64) {
+        crate::LOCAL_LABELS.with(|index| {
+            let mut index = index.borrow_mut();
+            let Some((labels, _)) = index.labels.remove(&identifier) else { return };
+
+            for selector in labels.selectors() {
+                if let Some(matched) = index.selectors.get_mut(&selector) {
+                    matched.retain(|other| *other != identifier);
+
+                    if matched.is_empty() {
+                        index.selectors.remove(&selector);
+                    }
+                }
+            }
+        });
+    }
+
+    /// The selectors matching these labels.
+    fn selectors(&self) -> Vec<Selector> {
+        [
+            self.id.clone().map(Selector::Id),
+            self.name.clone().map(Selector::Name),
+            self.test_id.clone().map(Selector::TestId)
+        ].into_iter().flatten().collect()
+    }
+}
+
+impl Selector {
+    pub fn matches(&self, labels: &Labels) -> bool {
+        let (wanted, label) = match self {
+            Selector::Id(id) => (id, &labels.id),
+            Selector::Name(name) => (name, &labels.name),
+            Selector::TestId(test_id) => (test_id, &labels.test_id)
+        };
+
+        label.as_ref() == Some(wanted)
+    }
+}
+
+/// `#id` selects by id and `@test_id` by test id, anything else selects by name.
+impl From<&str> for Selector {
+    fn from(selector: &str) -> Self {
+        if let Some(id) = selector.strip_prefix('#') {
+            Selector::Id(id.to_string())
+        } else if let Some(test_id) = selector.strip_prefix('@') {
+            Selector::TestId(test_id.to_string())
+        } else {
+            Selector::Name(selector.to_string())
+        }
+    }
+}
+
+
+impl Window {
+    /// The first element in this window's tree matching `selector`, in tree order.
+    pub fn find(&self, selector: impl Into<Selector>) -> Option<Element> {
+        self.find_all(selector).into_iter().next()
+    }
+
+    /// All elements in this window's tree matching `selector`, in tree order.
+    /// Children of portals are found in the tree of the window owning the portal, not the one they are drawn into.
+    pub fn find_all(&self, selector: impl Into<Selector>) -> Vec<Element> {
+        let selector = selector.into();
+
+        let matched: Vec<Element> = crate::LOCAL_LABELS.with(|index| {
+            let index = index.borrow();
+            let Some(identifiers) = index.selectors.get(&selector) else { return Vec::new() };
+
+            identifiers.iter()
+            .filter_map(|identifier| index.labels.get(identifier)?.1.upgrade_element())
+            .collect()
+        });
+
+        let mut found: Vec<(Vec<usize>, Element)> = matched.into_iter()
+        .filter_map(|element| Some((self.path_to(&element)?, element)))
+        .collect();
+        found.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        found.into_iter().map(|(_, element)| element).collect()
+    }
+
+    /// The child indices leading from this window down to `element`, which sort in tree order.
+    /// None if `element` isn't in this window's tree.
+    fn path_to(&self, element: &Element) -> Option<Vec<usize>> {
+        let root = Element::Window(self.clone()).identifier();
+        let mut path = Vec::new();
+        let mut current = element.clone();
+
+        while current.identifier() != root {
+            let parent = current.parent().as_ref()?.upgrade_element()?;
+            let index = parent.children().read().unwrap().iter()
+            .position(|child| child.identifier() == current.identifier())?;
+
+            path.push(index);
+            current = parent;
+        }
+
+        path.reverse();
+        Some(path)
+    }
+}
+
+impl ElementBuilder {
+    pub fn id(&self, id: impl Into<String>) -> Self {
+        self.label(|labels| labels.id = Some(id.into()))
+    }
+
+    pub fn name(&self, name: impl Into<String>) -> Self {
+        self.label(|labels| labels.name = Some(name.into()))
+    }
+
+    pub fn test_id(&self, test_id: impl Into<String>) -> Self {
+        self.label(|labels| labels.test_id = Some(test_id.into()))
+    }
+
+    fn label(&self, set: impl FnOnce(&mut Labels)) -> Self {
+        if let Some(lifecycle) = self.lifecycle() {
+            set(&mut lifecycle.labels.write().unwrap());
+        } else {
+            warn!("Tried to label a builder that doesn't build an element!");
+        }
+
+        self.clone()
+    }
+}
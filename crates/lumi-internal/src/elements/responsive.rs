@@ -0,0 +1,201 @@
+use std::{collections::BTreeMap, fmt::Debug, rc::Rc, sync::Arc};
+
+use crate::signals::{Signal, SignalTrait};
+
+use super::{dynamic::DynamicElementBuilder, element_builder::ElementBuilder, window::WindowState};
+
+
+/// A range of window widths to lay out for, from smallest to largest.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SizeClass {
+    #[default]
+    Compact,
+    Medium,
+    Expanded
+}
+
+/// The window widths at which each size class starts.
+/// A class without a threshold is never used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Breakpoints {
+    pub medium: Option<u32>,
+    pub expanded: Option<u32>,
+    /// How far past a threshold the width has to be before the class changes, so resizing around it doesn't flap between classes.
+    pub hysteresis: u32
+}
+
+/// Builds one subtree per size class, replacing it only when the class changes.
+pub struct ResponsiveBuilder {
+    class: Signal<SizeClass>,
+    layouts: BTreeMap<SizeClass, Rc<dyn Fn(ElementBuilder)>>
+}
+
+
+impl Breakpoints {
+    pub fn new(medium: u32, expanded: u32) -> Self {
+        Self { medium: Some(medium), expanded: Some(expanded), hysteresis: 8 }
+    }
+
+    pub fn with_hysteresis(self, hysteresis: u32) -> Self {
+        Self { hysteresis, ..self }
+    }
+
+    /// The size class for `width`, without hysteresis.
+    pub fn classify(&self, width: u32) -> SizeClass {
+        [(SizeClass::Expanded, self.expanded), (SizeClass::Medium, self.medium)].into_iter()
+        .find(|(_, threshold)| threshold.is_some_and(|threshold| width >= threshold))
+        .map_or(SizeClass::Compact, |(class, _)| class)
+    }
+
+    /// The class to switch to from `current`, once `width` is at least the hysteresis past a threshold.
+    fn next(&self, current: SizeClass, width: u32) -> SizeClass {
+        let larger = self.classify(width.saturating_sub(self.hysteresis));
+        let smaller = self.classify(width.saturating_add(self.hysteresis));
+
+        if larger > current {
+            larger
+        } else if smaller < current {
+            smaller
+        } else {
+            current
+        }
+    }
+}
+
+impl Default for Breakpoints {
+    /// Like Material's window size classes.
+    fn default() -> Self {
+        Self::new(600, 840)
+    }
+}
+
+impl WindowState {
+    /// The size class of this window's width, only updated when the class changes.
+    pub fn size_class(&self, breakpoints: Breakpoints) -> Signal<SizeClass> {
+        let width = self.dimensions.get().width;
+        let class = Signal::new(breakpoints.classify(width));
+
+        // Notified once the dimensions are released, as the layouts built for the new class may read them
+        let (cloned, dimensions) = (class.clone(), self.dimensions.clone());
+        self.dimensions.notify(move || {
+            let current = *cloned.get();
+            let next = breakpoints.next(current, dimensions.get().width);
+
+            if next != current {
+                cloned.set(next);
+            }
+        });
+
+        class
+    }
+}
+
+impl ResponsiveBuilder {
+    pub fn new(class: Signal<SizeClass>) -> Self {
+        Self { class, layouts: BTreeMap::new() }
+    }
+
+    /// Builds the subtree for `class` with `layout`.
+    /// Classes without a layout use the one of the closest smaller class, or else the closest larger one.
+    pub fn with(mut self, class: SizeClass, layout: impl Fn(ElementBuilder) + 'static) -> Self {
+        self.layouts.insert(class, Rc::new(layout));
+        self
+    }
+
+    /// The class whose layout is shown. Only changes when a different layout is picked, so the subtree is kept
+    /// while resizing within a class, or between classes sharing a layout.
+    fn picked(&self) -> Signal<Option<SizeClass>> {
+        let picked = Signal::new(Self::resolve(&self.layouts, *self.class.get()));
+        let (cloned, layouts) = (picked.clone(), self.layouts.clone());
+
+        self.class.subscribe(move |class| {
+            let next = Self::resolve(&layouts, *class);
+            if *cloned.get() != next {
+                cloned.set(next);
+            }
+        });
+
+        picked
+    }
+
+    /// The class whose layout is used for `class`.
+    fn resolve(layouts: &BTreeMap<SizeClass, Rc<dyn Fn(ElementBuilder)>>, class: SizeClass) -> Option<SizeClass> {
+        layouts.range(..=class).next_back()
+        .or_else(|| layouts.range(class..).next())
+        .map(|(class, _)| *class)
+    }
+}
+
+impl Debug for ResponsiveBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponsiveBuilder")
+        .field("class", &self.class)
+        .field("layouts", &self.layouts.keys().collect::<Vec<_>>())
+        .finish_non_exhaustive()
+    }
+}
+
+
+impl ElementBuilder {
+    /// Adds the subtree for the current size class of `responsive`.
+    pub fn responsive(&self, responsive: ResponsiveBuilder) -> Self {
+        let picked = responsive.picked();
+        let layouts = responsive.layouts;
+
+        let dynamic = DynamicElementBuilder::new(picked, move |picked, parent| {
+            if let Some(layout) = picked.and_then(|class| layouts.get(&class)) {
+                layout(parent);
+            }
+        });
+
+        self.child(ElementBuilder::from(Arc::new(dynamic)))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use crate::signals::{Signal, SignalTrait};
+
+    use super::{Breakpoints, ResponsiveBuilder, SizeClass};
+
+    #[test]
+    fn changes_class_past_the_hysteresis() {
+        let breakpoints = Breakpoints::new(600, 840).with_hysteresis(10);
+
+        assert_eq!(breakpoints.next(SizeClass::Compact, 605), SizeClass::Compact);
+        assert_eq!(breakpoints.next(SizeClass::Compact, 610), SizeClass::Medium);
+        assert_eq!(breakpoints.next(SizeClass::Compact, 900), SizeClass::Expanded);
+        assert_eq!(breakpoints.next(SizeClass::Medium, 595), SizeClass::Medium);
+        assert_eq!(breakpoints.next(SizeClass::Medium, 589), SizeClass::Compact);
+        assert_eq!(breakpoints.next(SizeClass::Expanded, 835), SizeClass::Expanded);
+        assert_eq!(breakpoints.next(SizeClass::Expanded, 400), SizeClass::Compact);
+    }
+
+    #[test]
+    fn rebuilds_only_when_the_picked_layout_changes() {
+        let class = Signal::new(SizeClass::Compact);
+        let responsive = ResponsiveBuilder::new(class.clone())
+        .with(SizeClass::Compact, |_| {})
+        .with(SizeClass::Expanded, |_| {});
+        let picked = responsive.picked();
+
+        // The dynamic element showing the layout is rebuilt every time this is set
+        let rebuilds = Rc::new(Cell::new(0));
+        let counted = rebuilds.clone();
+        picked.subscribe(move |_| counted.set(counted.get() + 1));
+
+        // Without a layout of its own, medium uses the compact one
+        class.set(SizeClass::Medium);
+        assert_eq!((*picked.get(), rebuilds.get()), (Some(SizeClass::Compact), 0));
+
+        class.set(SizeClass::Expanded);
+        assert_eq!((*picked.get(), rebuilds.get()), (Some(SizeClass::Expanded), 1));
+
+        class.set(SizeClass::Expanded);
+        class.set(SizeClass::Compact);
+        assert_eq!((*picked.get(), rebuilds.get()), (Some(SizeClass::Compact), 2));
+    }
+}
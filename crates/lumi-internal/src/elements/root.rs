@@ -4,7 +4,7 @@ use log::warn;
 
 use crate::backend::Backend;
 
-use super::{element::{Element, ElementRef, ElementRefTrait, ElementTrait}, element_builder::{ElementBuilder, ElementBuilderTrait}};
+use super::{element::{Element, ElementRef, ElementRefTrait, ElementTrait}, element_builder::{ElementBuilder, ElementBuilderTrait}, lifecycle::Lifecycle};
 
 
 #[derive(Debug, Clone)]
@@ -16,7 +16,8 @@ pub type RootElementRef = Weak<RwLock<Vec<Element>>>;
 
 #[derive(Debug)]
 pub struct RootElementBuilder {
-    children: RwLock<Vec<ElementBuilder>>
+    children: RwLock<Vec<ElementBuilder>>,
+    lifecycle: Lifecycle
 }
 
 impl ElementBuilderTrait for Arc<RootElementBuilder> {
@@ -36,14 +37,21 @@ impl ElementBuilderTrait for Arc<RootElementBuilder> {
         
         *temp_children.write().unwrap() = new_children.collect();
 
+        self.lifecycle.mounted(&element);
+
         element
     }
+
+    fn lifecycle(&self) -> Option<&Lifecycle> {
+        Some(&self.lifecycle)
+    }
 }
 
 impl RootElementBuilder {
     pub fn new() -> Self {
         Self {
-            children: RwLock::new(Vec::with_capacity(1))
+            children: RwLock::new(Vec::with_capacity(1)),
+            lifecycle: Lifecycle::default()
         }
     }
 }
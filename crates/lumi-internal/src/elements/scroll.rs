@@ -0,0 +1,284 @@
+use std::{cell::Cell, rc::Rc, sync::Arc};
+
+use lumi2d::types::{Position, Rounding};
+
+use crate::{geometry::{Bounds, Clip, Transform}, signals::{Signal, SignalTrait}, widgets::{rectangle::RectangleBuilder, widget_builder::WidgetBuilder}};
+
+use super::{dynamic::DynamicElementBuilder, element::{Element, ElementRefTrait, ElementTrait}, element_builder::ElementBuilder, group::GroupElementBuilder, lifecycle::on_cleanup};
+
+
+/// Pixels scrolled per line of mouse wheel movement
+const SCROLL_LINE: f64 = 40.0;
+const SCROLLBAR_WIDTH: u32 = 6;
+const SCROLLBAR_MIN_LENGTH: u32 = 20;
+
+/// Cuts off everything its children draw outside of a rectangle.
+/// Rectangles are cut down to the clip, while the renderer clips text, images and svgs reaching outside of it.
+#[derive(Debug, Default, Clone)]
+pub struct ClipBuilder {
+    pub x: Signal<i32>,
+    pub y: Signal<i32>,
+    pub width: Signal<u32>,
+    pub height: Signal<u32>,
+    pub rounding: Signal<Option<Rounding>>
+}
+
+/// A clipped viewport into larger content, scrolled with the mouse wheel, by dragging its scrollbars,
+/// or by dragging the content itself if `drag_content` is set.
+#[derive(Debug, Default, Clone)]
+pub struct ScrollViewBuilder {
+    pub x: Signal<i32>,
+    pub y: Signal<i32>,
+    pub width: Signal<u32>,
+    pub height: Signal<u32>,
+    /// The size of the scrolled content, which the offset can't scroll past.
+    pub content_width: Signal<u32>,
+    pub content_height: Signal<u32>,
+    /// How far the content is scrolled to the right and down, in pixels.
+    pub offset: Signal<(i32, i32)>,
+    pub rounding: Signal<Option<Rounding>>,
+    /// The color of the scrollbars, which are hidden if None.
+    pub scrollbars: Signal<Option<u32>>,
+    pub drag_content: bool
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Drag {
+    Content { cursor: (f64, f64), offset: (i32, i32) },
+    Vertical { cursor: f64, offset: i32 },
+    Horizontal { cursor: f64, offset: i32 }
+}
+
+
+impl ElementBuilder {
+    /// Adds a clip, and returns the builder to add the clipped children to.
+    pub fn clip(&self, clip: ClipBuilder) -> Self {
+        let bounds = (clip.x, clip.y, clip.width, clip.height).relative(|(x, y, w, h)| Bounds::new(**x, **y, **w, **h));
+        let clip = (bounds, clip.rounding).relative(|(bounds, rounding)| Clip::new(**bounds, rounding.cloned()));
+
+        self.child(ElementBuilder::Group(Arc::new(
+            GroupElementBuilder::new(Signal::constant(Transform::IDENTITY), Some(clip))
+        )))
+    }
+
+    /// Adds a scroll view, and returns the builder to add the scrolled content to.
+    /// Its children are positioned relative to the top left corner of the content.
+    pub fn scroll_view(&self, view: ScrollViewBuilder) -> Self {
+        let viewport = (view.x.clone(), view.y.clone(), view.width.clone(), view.height.clone())
+        .relative(|(x, y, w, h)| Bounds::new(**x, **y, **w, **h));
+        let content = (view.content_width.clone(), view.content_height.clone());
+
+        let max_offset = (viewport.clone(), content.0.clone(), content.1.clone()).relative(|(viewport, w, h)| {
+            (w.saturating_sub(viewport.width) as i32, h.saturating_sub(viewport.height) as i32)
+        });
+        // Kept within the content here, so setting the offset from outside can't scroll past it either
+        let offset = (view.offset.clone(), max_offset.clone()).relative(|(offset, max)| {
+            (offset.0.clamp(0, max.0), offset.1.clamp(0, max.1))
+        });
+
+        let clip = (viewport.clone(), view.rounding.clone()).relative(|(bounds, rounding)| Clip::new(**bounds, rounding.cloned()));
+        let transform = (viewport.clone(), offset.clone()).relative(|(viewport, offset)| {
+            Transform::translate((viewport.x - offset.0) as f32, (viewport.y - offset.1) as f32)
+        });
+
+        let outer = self.child(ElementBuilder::Group(Arc::new(
+            GroupElementBuilder::new(Signal::constant(Transform::IDENTITY), None)
+        )));
+        let scrolled = outer.child(ElementBuilder::Group(Arc::new(
+            GroupElementBuilder::new(transform, Some(clip))
+        )));
+
+        let vertical = (viewport.clone(), content.1.clone(), offset.clone()).relative(|(viewport, content, offset)| {
+            thumb(viewport.y, viewport.height, **content, offset.1)
+            .map_or_else(Bounds::default, |(y, height)| {
+                Bounds::new(viewport.right() - SCROLLBAR_WIDTH as i32, y, SCROLLBAR_WIDTH, height)
+            })
+        });
+        let horizontal = (viewport.clone(), content.0.clone(), offset.clone()).relative(|(viewport, content, offset)| {
+            thumb(viewport.x, viewport.width, **content, offset.0)
+            .map_or_else(Bounds::default, |(x, width)| {
+                Bounds::new(x, viewport.bottom() - SCROLLBAR_WIDTH as i32, width, SCROLLBAR_WIDTH)
+            })
+        });
+
+        // Only rebuilt when the scrollbars are shown or hidden, not when their color changes
+        let shown = Signal::new(view.scrollbars.get().is_some());
+        let cloned = shown.clone();
+        view.scrollbars.subscribe(move |color| if color.is_some() != *cloned.get() {
+            cloned.set(color.is_some());
+        });
+
+        let (bars, colors) = ([vertical.clone(), horizontal.clone()], view.scrollbars.clone());
+        let scrollbars = DynamicElementBuilder::new(shown, move |shown, parent| {
+            if !*shown {
+                return;
+            }
+
+            for bar in bars.iter() {
+                parent.child_widget(WidgetBuilder::from(RectangleBuilder {
+                    x: bar.relative(|b| b.x),
+                    y: bar.relative(|b| b.y),
+                    width: bar.relative(|b| b.width),
+                    height: bar.relative(|b| b.height),
+                    color: colors.relative(|color| color.unwrap_or(0)),
+                    rounding: Signal::constant(Some(Rounding::new_uniform(3))),
+                    ..Default::default()
+                }));
+            }
+        });
+        outer.child(ElementBuilder::from(Arc::new(scrollbars)));
+
+        let scroll_to = move |target: &Signal<(i32, i32)>, max: &Signal<(i32, i32)>, new: (i32, i32)| {
+            let max = *max.get();
+            target.set((new.0.clamp(0, max.0), new.1.clamp(0, max.1)));
+        };
+
+        outer.on_mount(move |element| {
+            let Some(window) = element.get_window() else { return };
+            let state = &window.innerest().state;
+            let drag = Rc::new(Cell::new(None::<Drag>));
+
+            // The window's signals can't drop subscriptions, so they are disabled once the view is unmounted
+            let mounted = Rc::new(Cell::new(true));
+            let cloned = mounted.clone();
+            on_cleanup(move || cloned.set(false));
+
+            // Where the viewport and scrollbars currently are in the window
+            let placement = (viewport.clone(), vertical.clone(), horizontal.clone(), element.get_transform(), element.get_clip())
+            .relative(|(viewport, vertical, horizontal, transform, clip)| {
+                let visible = |bounds: Bounds| {
+                    let bounds = transform.apply_bounds(bounds);
+                    match clip.as_ref() {
+                        Some(clip) => clip.cut(bounds).unwrap_or_default(),
+                        None => bounds
+                    }
+                };
+
+                (visible(**viewport), visible(**vertical), visible(**horizontal), transform.scale_x, transform.scale_y)
+            });
+
+            // Every view under the cursor claims the wheel movement first, and only the innermost one scrolls
+            let (identifier, depth) = (element.identifier(), depth(element));
+            let (cursor_pos, within, active) = (state.cursor_pos.clone(), placement.clone(), mounted.clone());
+            state.scroll.subscribe(move |_| {
+                let cursor = cursor_pos.get();
+
+                if active.get() && within.get().0.contains(cursor.x as f32, cursor.y as f32) {
+                    crate::LOCAL_SCROLL_TARGET.with(|claimed| {
+                        if claimed.get().is_none_or(|(claimed_depth, _)| depth > claimed_depth) {
+                            claimed.set(Some((depth, identifier)));
+                        }
+                    });
+                }
+            });
+
+            let (target, max, offset, scroll) = (view.offset.clone(), max_offset.clone(), offset.clone(), state.scroll.clone());
+            state.scroll.notify(move || {
+                let is_target = crate::LOCAL_SCROLL_TARGET.with(|claimed| {
+                    let is_target = claimed.get().is_some_and(|(_, claimed)| claimed == identifier);
+                    if is_target {
+                        claimed.set(None);
+                    }
+                    is_target
+                });
+
+                if is_target {
+                    let delta = *scroll.get();
+                    let current = *offset.get();
+                    let new = (
+                        current.0 - (delta.x * SCROLL_LINE).round() as i32,
+                        current.1 - (delta.y * SCROLL_LINE).round() as i32
+                    );
+                    scroll_to(&target, &max, new);
+                }
+            });
+
+            let (offset, cursor_pos, drag_start, active) = (offset.clone(), state.cursor_pos.clone(), drag.clone(), mounted.clone());
+            let (within, drag_content, has_scrollbars) = (placement.clone(), view.drag_content, view.scrollbars.clone());
+            state.click_left.subscribe(move |down| {
+                if !active.get() {
+                    return;
+                }
+
+                if !*down {
+                    drag_start.set(None);
+                    return;
+                }
+
+                let cursor = cursor_pos.get();
+                let (x, y) = (cursor.x as f32, cursor.y as f32);
+                let (viewport, vertical, horizontal, ..) = *within.get();
+                let current = *offset.get();
+
+                let started = if has_scrollbars.get().is_some() && vertical.contains(x, y) {
+                    Some(Drag::Vertical { cursor: cursor.y, offset: current.1 })
+                } else if has_scrollbars.get().is_some() && horizontal.contains(x, y) {
+                    Some(Drag::Horizontal { cursor: cursor.x, offset: current.0 })
+                } else if drag_content && viewport.contains(x, y) {
+                    Some(Drag::Content { cursor: (cursor.x, cursor.y), offset: current })
+                } else { None };
+
+                drag_start.set(started);
+            });
+
+            let (target, max, placement) = (view.offset.clone(), max_offset.clone(), placement.clone());
+            state.cursor_pos.subscribe(move |cursor: &Position<f64>| {
+                let Some(started) = drag.get().filter(|_| mounted.get()) else { return };
+                let (viewport, vertical, horizontal, scale_x, scale_y) = *placement.get();
+                let max_offset = *max.get();
+                let current = *target.get();
+
+                // Moves the content as far as the thumb moved, relative to the track it moves along
+                let along_track = |moved: f64, track: u32, thumb: u32, max: i32| {
+                    let free = track.saturating_sub(thumb).max(1) as f64;
+                    (moved * max as f64 / free).round() as i32
+                };
+
+                let new = match started {
+                    Drag::Content { cursor: start, offset } => (
+                        offset.0 - ((cursor.x - start.0) / scale_x as f64).round() as i32,
+                        offset.1 - ((cursor.y - start.1) / scale_y as f64).round() as i32
+                    ),
+                    Drag::Vertical { cursor: start, offset } => (
+                        current.0,
+                        offset + along_track(cursor.y - start, viewport.height, vertical.height, max_offset.1)
+                    ),
+                    Drag::Horizontal { cursor: start, offset } => (
+                        offset + along_track(cursor.x - start, viewport.width, horizontal.width, max_offset.0),
+                        current.1
+                    )
+                };
+
+                scroll_to(&target, &max, new);
+            });
+        });
+
+        scrolled
+    }
+}
+
+/// How many ancestors `element` has, to find the innermost of nested scroll views.
+fn depth(element: &Element) -> usize {
+    let mut depth = 0;
+    let mut parent = element.parent().as_ref().and_then(|parent| parent.upgrade_element());
+
+    while let Some(element) = parent {
+        depth += 1;
+        parent = element.parent().as_ref().and_then(|parent| parent.upgrade_element());
+    }
+
+    depth
+}
+
+/// The start and length of a scrollbar thumb along a viewport, if the content doesn't fit into it.
+fn thumb(start: i32, viewport: u32, content: u32, offset: i32) -> Option<(i32, u32)> {
+    if content <= viewport || viewport == 0 {
+        return None;
+    }
+
+    let length = ((viewport as u64 * viewport as u64 / content as u64) as u32).clamp(SCROLLBAR_MIN_LENGTH.min(viewport), viewport);
+    let max_offset = (content - viewport) as f64;
+    let travel = (viewport - length) as f64;
+
+    Some((start + (offset as f64 / max_offset * travel).round() as i32, length))
+}
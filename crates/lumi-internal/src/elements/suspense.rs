@@ -0,0 +1,189 @@
+use std::{cell::RefCell, collections::HashMap, fmt::Debug, future::Future, panic::{self, AssertUnwindSafe}, rc::{Rc, Weak}, sync::Arc, time::{Duration, Instant}};
+
+use crate::{geometry::Transform, signals::{FutureSignal, FutureState, Signal, SignalTrait}, timer::set_timeout};
+
+use super::{dynamic::{ChildBuilderContainer, DynamicElementBuilder}, element::{Element, ElementRefTrait, ElementTrait}, element_builder::{ElementBuilder, ElementBuilderTrait}, group::GroupElementBuilder};
+
+
+/// Shows a fallback instead of its children while any `FutureSignal` read during their build is still running.
+/// The children are built right away and kept while the fallback is shown, so their futures keep running.
+pub struct SuspenseBuilder {
+    fallback: Rc<dyn Fn(ElementBuilder)>,
+    min_duration: Duration,
+    /// Whether the fallback is currently shown.
+    pub pending: Signal<bool>
+}
+
+/// Tracks the futures read while the children of a suspense are built.
+#[derive(Debug, Clone)]
+pub(crate) struct Suspense {
+    inner: Rc<SuspenseInner>
+}
+
+#[derive(Debug)]
+struct SuspenseInner {
+    /// Whether each future is running, by the address of its data
+    futures: RefCell<HashMap<usize, bool>>,
+    running: Signal<bool>
+}
+
+
+impl SuspenseBuilder {
+    pub fn new(fallback: impl Fn(ElementBuilder) + 'static) -> Self {
+        Self {
+            fallback: Rc::new(fallback),
+            min_duration: Duration::ZERO,
+            pending: Signal::new(false)
+        }
+    }
+
+    /// Keeps the fallback around for at least `duration` once it is shown, so quick loads don't flicker.
+    pub fn with_min_duration(self, duration: Duration) -> Self {
+        Self {
+            min_duration: duration,
+            ..self
+        }
+    }
+}
+
+impl Debug for SuspenseBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SuspenseBuilder")
+        .field("min_duration", &self.min_duration)
+        .field("pending", &self.pending)
+        .finish_non_exhaustive()
+    }
+}
+
+
+impl Suspense {
+    fn new() -> Self {
+        Self {
+            inner: Rc::new(SuspenseInner {
+                futures: RefCell::new(HashMap::new()),
+                running: Signal::new(false)
+            })
+        }
+    }
+
+    /// Calls `f`, tracking every `FutureSignal` read inside of it.
+    /// The scope is left even if `f` panics, so an error boundary catching it doesn't keep tracking for this suspense.
+    pub(crate) fn track<R>(&self, f: impl FnOnce() -> R) -> R {
+        crate::LOCAL_SUSPENSE_SCOPES.with(|scopes| scopes.borrow_mut().push(self.clone()));
+        let result = panic::catch_unwind(AssertUnwindSafe(f));
+        crate::LOCAL_SUSPENSE_SCOPES.with(|scopes| scopes.borrow_mut().pop());
+
+        result.unwrap_or_else(|payload| panic::resume_unwind(payload))
+    }
+
+    /// Adds `future` to the suspense currently building, if there is one.
+    pub(crate) fn read<U: Send + Sync + 'static, T: Future<Output = U> + Send + 'static>(future: &FutureSignal<U, T>) {
+        let Some(suspense) = crate::LOCAL_SUSPENSE_SCOPES.with(|scopes| scopes.borrow().last().cloned()) else { return };
+        let key = Arc::as_ptr(&future.data) as *const () as usize;
+
+        if suspense.inner.futures.borrow().contains_key(&key) {
+            return;
+        }
+
+        let running = matches!(*future.data.read().unwrap(), FutureState::Running);
+        suspense.inner.futures.borrow_mut().insert(key, running);
+        suspense.update();
+
+        let weak = Rc::downgrade(&suspense.inner);
+        future.subscribe(move |state| {
+            let Some(inner) = Weak::upgrade(&weak) else { return };
+
+            inner.futures.borrow_mut().insert(key, matches!(state, FutureState::Running));
+            Suspense { inner }.update();
+        });
+
+        let weak = Rc::downgrade(&suspense.inner);
+        future.on_restart(move || {
+            let Some(inner) = Weak::upgrade(&weak) else { return };
+
+            inner.futures.borrow_mut().insert(key, true);
+            Suspense { inner }.update();
+        });
+    }
+
+    fn update(&self) {
+        let running = self.inner.futures.borrow().values().any(|running| *running);
+
+        if *self.inner.running.get() != running {
+            self.inner.running.set(running);
+        }
+    }
+
+    /// The closest suspense at or above `element`.
+    pub(crate) fn closest(element: &Element) -> Option<Suspense> {
+        let own = match element {
+            Element::Dynamic(dynamic) => dynamic.suspense().cloned(),
+            _ => None
+        };
+
+        own.or_else(|| {
+            let parent = element.parent().as_ref()?.upgrade_element()?;
+            Self::closest(&parent)
+        })
+    }
+}
+
+
+impl ElementBuilder {
+    /// Adds a suspense, and returns the builder to add the children it waits for to.
+    pub fn suspense(&self, suspense: SuspenseBuilder) -> Self {
+        let SuspenseBuilder { fallback, min_duration, pending } = suspense;
+        let tracker = Suspense::new();
+
+        // The fallback stays for at least min_duration, counted from when it was shown
+        let shown_at = Rc::new(RefCell::new(None::<Instant>));
+        let (shown, running) = (pending.clone(), tracker.inner.running.clone());
+        tracker.inner.running.subscribe(move |is_running| {
+            if *is_running {
+                shown_at.borrow_mut().get_or_insert_with(Instant::now);
+                if !*shown.get() {
+                    shown.set(true);
+                }
+                return;
+            }
+
+            let Some(since) = shown_at.borrow_mut().take() else { return };
+            let remaining = min_duration.saturating_sub(since.elapsed());
+
+            if remaining.is_zero() {
+                shown.set(false);
+            } else {
+                let (shown, running) = (shown.clone(), running.clone());
+                set_timeout(remaining, move || if !*running.get() {
+                    shown.set(false);
+                });
+            }
+        });
+
+        let outer = self.child(ElementBuilder::Group(Arc::new(
+            GroupElementBuilder::new(Signal::constant(Transform::IDENTITY), None)
+        )));
+
+        // Hidden rather than removed while pending, which also keeps its Interact widgets from being hit
+        let hidden = pending.relative(|pending| Transform::opacity(if *pending { 0.0 } else { 1.0 }));
+        let content = ChildBuilderContainer::new();
+        let children = content.clone();
+
+        outer.child(ElementBuilder::Group(Arc::new(GroupElementBuilder::new(hidden, None))))
+        .child(ElementBuilder::from(Arc::new(
+            DynamicElementBuilder::new(Signal::constant(()), move |_, parent| {
+                for child in children.children().read().unwrap().iter() {
+                    parent.child(child.clone());
+                }
+            }).with_suspense(tracker)
+        )));
+
+        outer.child(ElementBuilder::from(Arc::new(
+            DynamicElementBuilder::new(pending, move |pending, parent| if *pending {
+                fallback(parent);
+            })
+        )));
+
+        content.into()
+    }
+}
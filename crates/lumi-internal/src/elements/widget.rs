@@ -1,8 +1,10 @@
 use std::{fmt::Debug, sync::{Arc, RwLock, Weak}};
 
-use crate::{backend::Backend, widgets::{widget_builder::{WidgetBuilder, WidgetBuilderTrait}, Widget}};
+use log::warn;
 
-use super::{element::{Element, ElementRef, ElementRefTrait, ElementTrait}, element_builder::{ElementBuilder, ElementBuilderTrait}};
+use crate::{backend::Backend, signals::Signal, widgets::{widget_builder::{BuildContext, WidgetBuilder, WidgetBuilderTrait}, Widget}};
+
+use super::{element::{Element, ElementRef, ElementRefTrait, ElementTrait}, element_builder::{ElementBuilder, ElementBuilderTrait}, lifecycle::Lifecycle};
 
 
 #[derive(Debug, Clone)]
@@ -14,7 +16,9 @@ pub type WidgetElementRef = Weak<WidgetElementInner>;
 
 pub struct WidgetElementBuilder {
     widget: WidgetBuilder,
-    children: RwLock<Vec<ElementBuilder>>
+    children: RwLock<Vec<ElementBuilder>>,
+    lifecycle: Lifecycle,
+    z_index: RwLock<Option<Signal<i32>>>
 }
 
 
@@ -23,17 +27,19 @@ pub struct WidgetElementInner {
     pub(crate) parent: Option<ElementRef>,
     pub(crate) widget: Widget,
     pub(crate) children: RwLock<Vec<Element>>,
-    pub(crate) identifier: u64
+    pub(crate) identifier: u64,
+    pub(crate) z_index: Option<Signal<i32>>
 }
 
 impl WidgetElement {
-    pub fn new(parent: Option<ElementRef>, widget: Widget, children: RwLock<Vec<Element>>) -> Self {
+    pub fn new(parent: Option<ElementRef>, widget: Widget, children: RwLock<Vec<Element>>, z_index: Option<Signal<i32>>) -> Self {
         Self {
             inner: Arc::new(WidgetElementInner {
                 parent,
                 widget,
                 children,
-                identifier: fastrand::u64(..)
+                identifier: fastrand::u64(..),
+                z_index
             })
         }
     }
@@ -41,6 +47,11 @@ impl WidgetElement {
     pub fn widget(&self) -> &Widget {
         &self.inner.widget
     }
+
+    /// The z-index set on this element, which its descendants inherit.
+    pub fn z_index(&self) -> Option<&Signal<i32>> {
+        self.inner.z_index.as_ref()
+    }
 }
 
 impl ElementRefTrait for WidgetElementRef {
@@ -82,9 +93,6 @@ impl ElementBuilderTrait for Arc<WidgetElementBuilder> {
 
     fn build(&self, backend: &Backend, parent: Option<ElementRef>) -> Element {
         let children = self.children.read().unwrap();
-        let window = parent.as_ref()
-        .and_then(|p| p.upgrade_element())
-        .and_then(|p| p.get_window());
         
         // TODO: Make this part of the trait somehow?
         let element = match &self.widget {
@@ -94,11 +102,16 @@ impl ElementBuilderTrait for Arc<WidgetElementBuilder> {
                 Vec::with_capacity(children.len()),
                 builder
             ),
-            widget => Element::new_widget(
-                parent,
-                Vec::with_capacity(children.len()),
-                widget.build(backend, window.as_ref())
-            ),
+            widget => {
+                let context = BuildContext::new(parent.as_ref());
+
+                Element::new_widget(
+                    parent,
+                    Vec::with_capacity(children.len()),
+                    widget.build(backend, &context),
+                    self.z_index.read().unwrap().clone()
+                )
+            },
         };
 
 
@@ -111,24 +124,51 @@ impl ElementBuilderTrait for Arc<WidgetElementBuilder> {
             .collect();
         }
 
+        self.lifecycle.mounted(&element);
+
         element
     }
+
+    fn lifecycle(&self) -> Option<&Lifecycle> {
+        Some(&self.lifecycle)
+    }
 }
 
 impl WidgetElementBuilder {
     pub fn new(children: Vec<ElementBuilder>, widget: WidgetBuilder) -> Self {
         Self {
             children: RwLock::new(children),
-            widget
+            widget,
+            lifecycle: Lifecycle::default(),
+            z_index: RwLock::new(None)
         }
     }
 }
 
+impl ElementBuilder {
+    /// Draws the widget built from this builder, and everything below it, above elements with a lower z-index.
+    /// Elements with equal z-indices keep their tree order, and `Interact` widgets only receive input if no other `Interact` is drawn above them.
+    ///
+    /// Other widgets don't block input, even if they are opaque. To keep a panel or a modal's backdrop
+    /// from passing clicks to what's below it, give it an `Interact` of the same size.
+    pub fn z_index(&self, z_index: Signal<i32>) -> Self {
+        if let ElementBuilder::Widget(widget) = self {
+            *widget.z_index.write().unwrap() = Some(z_index);
+        } else {
+            warn!("Tried to set a z-index on a builder that doesn't build a widget!");
+        }
+
+        self.clone()
+    }
+}
+
 impl Debug for WidgetElementBuilder {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Element")
         .field("widget", &self.widget)
         .field("children", &self.children.read().unwrap())
+        .field("lifecycle", &self.lifecycle)
+        .field("z_index", &self.z_index.read().unwrap())
         .finish_non_exhaustive()
     }
 }
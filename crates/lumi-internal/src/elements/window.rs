@@ -1,10 +1,12 @@
 use lumi2d::{prelude::*, renderer::RResult, types::Window as LumiWindow};
 
-use std::{ops::Deref, sync::{Arc, RwLock, Weak}, time::Instant};
+use std::{ops::Deref, sync::{Arc, RwLock, Weak}};
 
-use crate::{backend::Backend, signals::{Signal, SignalTrait}, widgets::{widget_builder::WidgetBuilderTrait, Widget, WidgetTrait}};
+use log::info;
 
-use super::element::*;
+use crate::{backend::Backend, custom_event::CustomEvent, signals::{Signal, SignalTrait}, timer::FrameCallbacks, widgets::{interact::HitTargets, widget_builder::{BuildContext, WidgetBuilderTrait}, Widget, WidgetTrait}};
+
+use super::{debug::TreeFormat, element::*, lifecycle::Cleanups, portal::Layers};
 
 
 
@@ -20,6 +22,9 @@ pub struct WindowElement {
     pub(crate) parent: Option<ElementRef>,
     pub(crate) inner: WindowInner, // TODO: Make this Send + Sync, somehow
     pub(crate) children: RwLock<Vec<Element>>,
+    pub(crate) frame_callbacks: FrameCallbacks,
+    pub(crate) layers: Layers,
+    pub(crate) hit_targets: HitTargets,
     identifier: u64
 }
 
@@ -45,12 +50,21 @@ impl ElementTrait for Window {
         ElementRef::Window(Arc::downgrade(&self.inner))
     }
     fn destruct(self, backend: &Backend) {
+        Cleanups::run_attached(self.identifier());
+
         for child in self.children().write().unwrap().drain(..) {
             child.destruct(backend);
         }
 
         if let Some(window) = backend.take_window(&self.id()) {
             drop(window);
+
+            #[cfg(feature = "hot_reload")]
+            if crate::hot_reload::is_reloading() {
+                crate::hot_reload::retain_window(Arc::into_inner(self.inner).unwrap().inner);
+                return;
+            }
+
             self.close(&backend.renderer_data());
         }
     }
@@ -61,7 +75,9 @@ impl ElementTrait for Window {
 pub struct WindowInner {
     pub(crate) state: WindowState,
     pub(crate) window: LumiWindow,
-    pub(crate) renderer: Renderer
+    pub(crate) renderer: Renderer,
+    /// What the window was created with
+    pub(crate) details: WindowDetails
 }
 
 #[derive(Debug, Clone)]
@@ -72,6 +88,8 @@ pub struct WindowState {
     pub click_right: Signal<bool>,
     pub click_middle: Signal<bool>,
     pub focused: Signal<bool>,
+    /// The last movement of the mouse wheel, in lines.
+    pub scroll: Signal<Position<f64>>
 }
 
 
@@ -82,15 +100,15 @@ pub struct WindowBuilder {
 }
 
 impl WidgetBuilderTrait for WindowBuilder {
-    fn build(&self, _: &Backend, _: Option<&Window>) -> Widget {
+    fn build(&self, _: &Backend, _: &BuildContext) -> Widget {
         unreachable!();
     }
 }
 
 
 impl Window {
-    pub(crate) fn create_inner(window: LumiWindow, renderer: Renderer, state: WindowState) -> WindowInner {
-        WindowInner { window, renderer, state }
+    pub(crate) fn create_inner(window: LumiWindow, renderer: Renderer, details: WindowDetails, state: WindowState) -> WindowInner {
+        WindowInner { window, renderer, details, state }
     }
 
     pub(crate) fn create(inner: WindowInner, parent: Option<ElementRef>, children: Vec<Element>) -> Window {
@@ -98,13 +116,15 @@ impl Window {
             parent,
             inner,
             children: RwLock::new(children),
+            frame_callbacks: FrameCallbacks::default(),
+            layers: Layers::default(),
+            hit_targets: HitTargets::default(),
             identifier: fastrand::u64(..)
         };
 
         Window { inner: Arc::new(element) }
     }
 
-    #[allow(unused)]
     pub(crate) fn weak(&self) -> WindowRef {
         Arc::downgrade(&self.inner)
     }
@@ -161,16 +181,21 @@ impl Window {
                 WindowEvent::FocusChange(focus) => {
                     state.focused.set(focus)
                 }
+                WindowEvent::MouseScroll(delta) => {
+                    state.scroll.set(delta)
+                }
+                // Logs the tree of the focused window, in debug builds only
+                WindowEvent::Key(Key::F12, KeyAction::Press) if cfg!(debug_assertions) && *state.focused.get() => {
+                    info!("Element tree of window {:?}:\n{}", self.id(), Element::Window(self.clone()).debug_tree(TreeFormat::Text));
+                }
                 _ => {}
             }
         }
 
-        crate::LOCAL_FRAME_NOTIFIER.with(|notifier| {
-            if notifier.run(Instant::now()) {
-                crate::global_send(Event::Custom(crate::custom_event::CustomEvent::Redraw(self.id())));
-            }
-        });
-        
+        if self.inner.frame_callbacks.run() {
+            crate::global_send(Event::Custom(CustomEvent::Redraw(self.id())));
+        }
+
         self.draw_children(&backend.renderer_data());
         //let cursor = self.inner.inner.state.cursor_pos.get();
         //self.render(vec![&Objects::rectangle(cursor.x as _, cursor.y as _, 10, 10, 0xFFFFFFFF, None)]).unwrap();
@@ -188,6 +213,15 @@ impl Window {
         for child in self.children().read().unwrap().iter() {
             child.render_into(&mut elements)
         }
+        sort_by_z_index(&mut elements);
+
+        // Layers stay above the window's own children, no matter their z-indices
+        let mut layered = Vec::new();
+        self.inner.layers.render_into(&mut layered);
+        sort_by_z_index(&mut layered);
+        elements.append(&mut layered);
+
+        self.inner.hit_targets.update(&elements);
 
         let objects = elements
         .iter()
@@ -207,8 +241,13 @@ impl Window {
     }
 }
 
+/// Stable, so elements with equal z-indices stay in tree order.
+fn sort_by_z_index(elements: &mut [Element]) {
+    elements.sort_by_cached_key(|element| element.z_index());
+}
+
 impl WindowInner {
-    fn close(self, renderer_data: &RendererData) {
+    pub(crate) fn close(self, renderer_data: &RendererData) {
         self.window.close(renderer_data)
     }
 
@@ -216,6 +255,21 @@ impl WindowInner {
         self.window.id()
     }
 
+    /// Keeps the window and renderer of a window from before a hot reload, with the state of the new tree.
+    #[cfg(feature = "hot_reload")]
+    pub(crate) fn reuse(self, state: WindowState) -> WindowInner {
+        state.dimensions.set(self.state.dimensions.get().cloned());
+
+        WindowInner { state, ..self }
+    }
+
+    /// Whether the window can be taken over by a window built with `details`.
+    /// Their sizes aren't compared, as a reused window keeps its size.
+    #[cfg(feature = "hot_reload")]
+    pub(crate) fn has_details(&self, details: &WindowDetails) -> bool {
+        self.details.title == details.title
+    }
+
     fn resized(&self, size: Dimensions<u32>, renderer_data: &RendererData) {
         self.state.dimensions.set(size);
         self.renderer.recreate(&self.window, renderer_data);
@@ -231,7 +285,8 @@ impl Default for WindowState {
             click_left: Signal::new(false),
             click_right: Signal::new(false),
             click_middle: Signal::new(false),
-            focused: Signal::new(false)
+            focused: Signal::new(false),
+            scroll: Signal::new(Position::new(0.0, 0.0))
         }
     }
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-use std::{cell::{RefCell, RefMut}, time::Instant};
+use std::{cell::{RefCell, RefMut}, fmt::Debug, time::Instant};
 
 pub struct FrameNotifier {
     pub listeners: RefCell<Vec<FrameListener>>
@@ -25,22 +25,39 @@ impl FrameNotifier {
         self.get_mut().push(listener);
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.listeners.borrow().is_empty()
+    }
+
     // returns true if there are still active listeners
     pub fn run(&self, time: Instant) -> bool {
-        let mut lock = self.get_mut();
-      
-        for listener in lock.iter_mut() {
+        // Taken out, so listeners can add new listeners while running (e.g. by setting an animated signal)
+        let mut running = std::mem::take(&mut *self.get_mut());
+
+        for listener in running.iter_mut() {
             (listener.callback)(time)
         }
 
-        lock.retain(|listener| {
+        running.retain(|listener| {
             listener.expires > time
         });
 
+        let mut lock = self.get_mut();
+        running.append(&mut lock);
+        *lock = running;
+
         lock.len() > 0
     }
 }
 
+impl Debug for FrameNotifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FrameNotifier")
+        .field("Listener count", &self.listeners.borrow().len())
+        .finish()
+    }
+}
+
 
 impl FrameListener {
     pub fn new(callback: impl Fn(Instant) + 'static, expires: Instant) -> Self {
@@ -49,4 +66,4 @@ impl FrameListener {
             expires
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,383 @@
+use lumi2d::types::{Object, Rounding};
+
+/// An axis aligned rectangle in window pixels.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32
+}
+
+impl Bounds {
+    pub const fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    pub fn right(&self) -> i32 {
+        self.x.saturating_add(self.width as i32)
+    }
+
+    pub fn bottom(&self) -> i32 {
+        self.y.saturating_add(self.height as i32)
+    }
+
+    pub fn center(&self) -> (f32, f32) {
+        (
+            self.x as f32 + self.width as f32 / 2.0,
+            self.y as f32 + self.height as f32 / 2.0
+        )
+    }
+
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x > self.x as f32 && y > self.y as f32 && x < self.right() as f32 && y < self.bottom() as f32
+    }
+
+    /// The smallest bounds containing both `self` and `other`.
+    pub fn union(self, other: Bounds) -> Bounds {
+        let (x, y) = (self.x.min(other.x), self.y.min(other.y));
+        let (right, bottom) = (self.right().max(other.right()), self.bottom().max(other.bottom()));
+
+        Bounds::new(x, y, right.abs_diff(x), bottom.abs_diff(y))
+    }
+
+    /// Linearly interpolates between `self` (at 0.0) and `other` (at 1.0).
+    pub fn lerp(self, other: Bounds, progress: f32) -> Bounds {
+        let lerp = |a: f32, b: f32| a + (b - a) * progress;
+
+        Bounds::new(
+            lerp(self.x as f32, other.x as f32).round() as i32,
+            lerp(self.y as f32, other.y as f32).round() as i32,
+            lerp(self.width as f32, other.width as f32).round() as u32,
+            lerp(self.height as f32, other.height as f32).round() as u32
+        )
+    }
+
+    /// The overlapping area of both bounds, if there is one.
+    pub fn intersection(self, other: Bounds) -> Option<Bounds> {
+        let (x, y) = (self.x.max(other.x), self.y.max(other.y));
+        let (right, bottom) = (self.right().min(other.right()), self.bottom().min(other.bottom()));
+
+        if right > x && bottom > y {
+            Some(Bounds::new(x, y, right.abs_diff(x), bottom.abs_diff(y)))
+        } else {
+            None
+        }
+    }
+}
+
+
+/// An area outside of which nothing is drawn, in window pixels.
+#[derive(Debug, Clone)]
+pub struct Clip {
+    pub bounds: Bounds,
+    /// The rounding of the clip's corners.
+    pub rounding: Option<Rounding>
+}
+
+impl Clip {
+    pub fn new(bounds: Bounds, rounding: Option<Rounding>) -> Self {
+        Self { bounds, rounding }
+    }
+
+    /// The part of `bounds` inside of the clip, if any.
+    pub fn cut(&self, bounds: Bounds) -> Option<Bounds> {
+        self.bounds.intersection(bounds)
+    }
+
+    pub fn intersects(&self, bounds: Bounds) -> bool {
+        self.cut(bounds).is_some()
+    }
+
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        self.bounds.contains(x, y)
+    }
+
+    /// The rounding of a rectangle at `bounds` with the rounding `own`, once it is cut down to `cut`.
+    /// Corners lying on a corner of the clip are rounded like it, the rectangle's own corners keep their rounding,
+    /// and the corners made by cutting it are square.
+    pub fn corner_rounding(&self, bounds: Bounds, cut: Bounds, own: Option<&Rounding>) -> Option<Rounding> {
+        let (left, top) = (cut.x == bounds.x, cut.y == bounds.y);
+        let (right, bottom) = (cut.right() == bounds.right(), cut.bottom() == bounds.bottom());
+        let (clip_left, clip_top) = (cut.x == self.bounds.x, cut.y == self.bounds.y);
+        let (clip_right, clip_bottom) = (cut.right() == self.bounds.right(), cut.bottom() == self.bounds.bottom());
+
+        let corner = |kept: bool, on_clip: bool, radius: fn(&Rounding) -> u32| {
+            let own = own.filter(|_| kept).map_or(0, radius);
+            let clip = self.rounding.as_ref().filter(|_| on_clip).map_or(0, radius);
+
+            own.max(clip)
+        };
+
+        let rounding = Rounding {
+            top_left: corner(left && top, clip_left && clip_top, |rounding| rounding.top_left),
+            top_right: corner(right && top, clip_right && clip_top, |rounding| rounding.top_right),
+            bottom_right: corner(right && bottom, clip_right && clip_bottom, |rounding| rounding.bottom_right),
+            bottom_left: corner(left && bottom, clip_left && clip_bottom, |rounding| rounding.bottom_left)
+        };
+
+        let square = [rounding.top_left, rounding.top_right, rounding.bottom_right, rounding.bottom_left] == [0; 4];
+        (!square).then_some(rounding)
+    }
+
+    /// Has the renderer cut off the parts of `object` outside of the clip, for objects which can't be cut down like rectangles.
+    /// `bounds` is the area the object covers, objects entirely inside of an unrounded clip are returned as they are.
+    pub fn apply(&self, bounds: Bounds, object: Object) -> Object {
+        if self.rounding.is_none() && self.cut(bounds) == Some(bounds) {
+            return object;
+        }
+
+        let Bounds { x, y, width, height } = self.bounds;
+        Object::clipped(object, x, y, width, height, self.rounding.clone())
+    }
+
+    /// Clips to the area inside of both clips, keeping the rounding of `self`.
+    pub fn intersect(&self, outer: &Clip) -> Clip {
+        let bounds = self.cut(outer.bounds)
+        .unwrap_or(Bounds::new(self.bounds.x, self.bounds.y, 0, 0));
+
+        Clip::new(bounds, self.rounding.clone())
+    }
+}
+
+
+/// Translation, rotation, scale and opacity, applied to a widget's objects when it is drawn.
+/// Points are scaled first, then rotated clockwise by `rotation` radians, and translated last.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub translate_x: f32,
+    pub translate_y: f32,
+    pub scale_x: f32,
+    pub scale_y: f32,
+    pub rotation: f32,
+    pub opacity: f32
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform {
+        translate_x: 0.0,
+        translate_y: 0.0,
+        scale_x: 1.0,
+        scale_y: 1.0,
+        rotation: 0.0,
+        opacity: 1.0
+    };
+
+    pub fn translate(x: f32, y: f32) -> Self {
+        Self { translate_x: x, translate_y: y, ..Self::IDENTITY }
+    }
+
+    pub fn scale(x: f32, y: f32) -> Self {
+        Self { scale_x: x, scale_y: y, ..Self::IDENTITY }
+    }
+
+    /// Scales around `(origin_x, origin_y)` instead of the top left corner of the window.
+    pub fn scale_around(x: f32, y: f32, origin_x: f32, origin_y: f32) -> Self {
+        Self {
+            translate_x: origin_x - origin_x * x,
+            translate_y: origin_y - origin_y * y,
+            ..Self::scale(x, y)
+        }
+    }
+
+    /// Rotates clockwise by `angle` radians.
+    pub fn rotate(angle: f32) -> Self {
+        Self { rotation: angle, ..Self::IDENTITY }
+    }
+
+    /// Rotates around `(origin_x, origin_y)` instead of the top left corner of the window.
+    pub fn rotate_around(angle: f32, origin_x: f32, origin_y: f32) -> Self {
+        let (x, y) = Self::rotate(angle).apply_point(origin_x, origin_y);
+
+        Self {
+            translate_x: origin_x - x,
+            translate_y: origin_y - y,
+            ..Self::rotate(angle)
+        }
+    }
+
+    pub fn opacity(opacity: f32) -> Self {
+        Self { opacity, ..Self::IDENTITY }
+    }
+
+    /// Returns a transform that applies `inner` first and `self` afterwards.
+    /// Exact unless `self` scales unevenly and `inner` rotates, which would skew and is approximated by scaling after rotating.
+    pub fn compose(&self, inner: &Transform) -> Transform {
+        let (translate_x, translate_y) = self.apply_point(inner.translate_x, inner.translate_y);
+
+        Transform {
+            translate_x,
+            translate_y,
+            scale_x: self.scale_x * inner.scale_x,
+            scale_y: self.scale_y * inner.scale_y,
+            rotation: self.rotation + inner.rotation,
+            opacity: self.opacity * inner.opacity
+        }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+
+    pub fn is_rotated(&self) -> bool {
+        self.rotation.rem_euclid(std::f32::consts::TAU) != 0.0
+    }
+
+    pub fn apply_point(&self, x: f32, y: f32) -> (f32, f32) {
+        let (x, y) = (x * self.scale_x, y * self.scale_y);
+        let (sin, cos) = self.rotation.sin_cos();
+
+        (
+            x * cos - y * sin + self.translate_x,
+            x * sin + y * cos + self.translate_y
+        )
+    }
+
+    /// Maps a point in window space back into the untransformed space, e.g. for hit tests.
+    /// Returns None if the transform is not invertible.
+    pub fn invert_point(&self, x: f32, y: f32) -> Option<(f32, f32)> {
+        if self.scale_x == 0.0 || self.scale_y == 0.0 {
+            return None;
+        }
+
+        let (x, y) = (x - self.translate_x, y - self.translate_y);
+        let (sin, cos) = self.rotation.sin_cos();
+
+        Some((
+            (x * cos + y * sin) / self.scale_x,
+            (y * cos - x * sin) / self.scale_y
+        ))
+    }
+
+    /// The smallest axis aligned bounds containing `bounds` once transformed.
+    pub fn apply_bounds(&self, bounds: Bounds) -> Bounds {
+        if self.is_identity() {
+            return bounds;
+        }
+
+        Self::around(corners(bounds).map(|(x, y)| self.apply_point(x, y)))
+    }
+
+    /// The smallest axis aligned bounds containing window space `bounds` once mapped back into the untransformed space.
+    /// Returns None if the transform is not invertible.
+    pub fn invert_bounds(&self, bounds: Bounds) -> Option<Bounds> {
+        if self.is_identity() {
+            return Some(bounds);
+        }
+
+        let [a, b, c, d] = corners(bounds);
+        let inverted = [
+            self.invert_point(a.0, a.1)?,
+            self.invert_point(b.0, b.1)?,
+            self.invert_point(c.0, c.1)?,
+            self.invert_point(d.0, d.1)?
+        ];
+
+        Some(Self::around(inverted))
+    }
+
+    fn around(corners: [(f32, f32); 4]) -> Bounds {
+        let (x1, y1) = corners.iter().fold((f32::MAX, f32::MAX), |(x, y), corner| (x.min(corner.0), y.min(corner.1)));
+        let (x2, y2) = corners.iter().fold((f32::MIN, f32::MIN), |(x, y), corner| (x.max(corner.0), y.max(corner.1)));
+
+        Bounds::new(
+            x1.round() as i32,
+            y1.round() as i32,
+            (x2 - x1).round() as u32,
+            (y2 - y1).round() as u32
+        )
+    }
+
+    /// Where to draw an object covering `bounds` before it is rotated with [`Transform::rotate_object`]:
+    /// scaled, with its top left corner where the transform moves it.
+    pub fn place_bounds(&self, bounds: Bounds) -> Bounds {
+        if !self.is_rotated() {
+            return self.apply_bounds(bounds);
+        }
+
+        let (x, y) = self.apply_point(bounds.x as f32, bounds.y as f32);
+
+        Bounds::new(
+            x.round() as i32,
+            y.round() as i32,
+            (bounds.width as f32 * self.scale_x.abs()).round() as u32,
+            (bounds.height as f32 * self.scale_y.abs()).round() as u32
+        )
+    }
+
+    /// Has the renderer rotate an object drawn at `placed` (from [`Transform::place_bounds`]) around its top left corner.
+    pub fn rotate_object(&self, placed: Bounds, object: Object) -> Object {
+        if !self.is_rotated() {
+            return object;
+        }
+
+        Object::rotated(object, self.rotation, placed.x as f32, placed.y as f32)
+    }
+
+    /// Clips stay axis aligned, so a rotated clip is widened to the bounds around it.
+    pub fn apply_clip(&self, clip: &Clip) -> Clip {
+        Clip::new(self.apply_bounds(clip.bounds), clip.rounding.clone())
+    }
+
+    /// Multiplies the alpha channel of an RGBA color with the opacity.
+    pub fn apply_color(&self, color: u32) -> u32 {
+        if self.opacity >= 1.0 {
+            return color;
+        }
+
+        let alpha = ((color & 0xFF) as f32 * self.opacity.max(0.0)).round() as u32;
+
+        (color & !0xFF) | alpha.min(0xFF)
+    }
+
+    /// Whether anything drawn with this transform would be invisible.
+    pub fn is_invisible(&self) -> bool {
+        self.opacity <= 0.0 || self.scale_x == 0.0 || self.scale_y == 0.0
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+/// The top left, top right, bottom right and bottom left corners of `bounds`.
+fn corners(bounds: Bounds) -> [(f32, f32); 4] {
+    let (left, top, right, bottom) = (bounds.x as f32, bounds.y as f32, bounds.right() as f32, bounds.bottom() as f32);
+
+    [(left, top), (right, top), (right, bottom), (left, bottom)]
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::{Bounds, Transform};
+
+    fn assert_close((x, y): (f32, f32), expected: (f32, f32)) {
+        assert!((x - expected.0).abs() < 1e-3 && (y - expected.1).abs() < 1e-3, "{:?} != {:?}", (x, y), expected);
+    }
+
+    #[test]
+    fn rotates_around_origin() {
+        let transform = Transform::rotate_around(FRAC_PI_2, 10.0, 10.0);
+
+        assert_close(transform.apply_point(20.0, 10.0), (10.0, 20.0));
+        assert_close(transform.invert_point(10.0, 20.0).unwrap(), (20.0, 10.0));
+        assert_eq!(transform.apply_bounds(Bounds::new(10, 10, 20, 10)), Bounds::new(0, 10, 10, 20));
+        assert_eq!(transform.invert_bounds(Bounds::new(0, 10, 10, 20)), Some(Bounds::new(10, 10, 20, 10)));
+    }
+
+    #[test]
+    fn composed_transforms_apply_inner_first() {
+        let outer = Transform::translate(5.0, -5.0).compose(&Transform::scale(2.0, 2.0));
+        let inner = Transform::rotate_around(0.5, 3.0, 4.0).compose(&Transform::scale(1.5, 0.5));
+        let composed = outer.compose(&inner);
+
+        let (x, y) = inner.apply_point(7.0, -2.0);
+        assert_close(composed.apply_point(7.0, -2.0), outer.apply_point(x, y));
+        assert_close(composed.invert_point(x * 2.0 + 5.0, y * 2.0 - 5.0).unwrap(), (7.0, -2.0));
+    }
+}
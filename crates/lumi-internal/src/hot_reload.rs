@@ -0,0 +1,297 @@
+use std::{any::Any, cell::{Cell, RefCell}, fs, io, mem, panic::{self, AssertUnwindSafe}, path::{Path, PathBuf}, process::Command, rc::Rc, sync::{atomic::{AtomicBool, Ordering}, Arc}, thread, time::{Duration, SystemTime}};
+
+use libloading::Library;
+use log::{error, info, warn};
+use lumi2d::types::Event;
+
+use crate::{backend::Backend, custom_event::CustomEvent, elements::{element::{Element, ElementTrait}, element_builder::{ElementBuilder, ElementBuilderTrait}, window::WindowInner}, signals::Signal, timer::set_interval};
+
+
+/// The function a hot reloaded library exports, which adds the UI to the root builder it is given.
+/// Export it with `#[unsafe(no_mangle)]`, and link both the app and the library against `lumi-dylib`
+/// (the `dynamic_linking` feature), so they share the same copy of LumiUI.
+pub type BuildUi = fn(&ElementBuilder);
+
+/// Rebuilds the UI from a function in a dynamic library whenever that library changes,
+/// keeping the values of signals created with [`hot_signal`] and the open windows, which the new tree's windows
+/// take over in order (keeping their size) unless one was built with a different title.
+/// If building the new tree panics, the previous one is built again.
+#[derive(Debug, Clone)]
+pub struct HotReload {
+    library: PathBuf,
+    symbol: String,
+    sources: Option<(PathBuf, Vec<String>)>,
+    interval: Duration
+}
+
+
+/// Returns the signal created for `key` before the last reload, or a new one starting at `initial`.
+/// A previous signal is only reused if it holds the same type, with the same size and alignment.
+///
+/// Types defined in the reloaded library keep their type id when their fields change,
+/// and the size check can't tell if fields of the same size were swapped or changed.
+/// Change the key (e.g. `"settings/v2"`) along with such a type, as reading the old value would be undefined behavior.
+pub fn hot_signal<T: 'static>(key: &str, initial: impl FnOnce() -> T) -> Signal<T> {
+    crate::LOCAL_HOT_SIGNALS.with(|signals| {
+        let mut signals = signals.borrow_mut();
+        let layout = (mem::size_of::<T>(), mem::align_of::<T>());
+
+        let previous = signals.get(key)
+        .filter(|(previous, _)| *previous == layout)
+        .and_then(|(_, signal)| signal.downcast_ref::<Signal<T>>());
+
+        if let Some(signal) = previous {
+            return signal.clone();
+        }
+
+        let signal = Signal::new(initial());
+        signals.insert(key.to_string(), (layout, Box::new(signal.clone()) as Box<dyn Any>));
+
+        signal
+    })
+}
+
+
+impl HotReload {
+    /// Loads `symbol` from the library at `library`, a [`BuildUi`] function.
+    pub fn new(library: impl Into<PathBuf>, symbol: impl Into<String>) -> Self {
+        Self {
+            library: library.into(),
+            symbol: symbol.into(),
+            sources: None,
+            interval: Duration::from_millis(500)
+        }
+    }
+
+    /// Runs `command` (e.g. `["cargo", "build", "-p", "my-ui"]`) whenever a file in `sources` changes,
+    /// which is expected to rebuild the library.
+    pub fn rebuild_on_change(self, sources: impl Into<PathBuf>, command: &[&str]) -> Self {
+        Self {
+            sources: Some((sources.into(), command.iter().map(|arg| arg.to_string()).collect())),
+            ..self
+        }
+    }
+
+    /// How often the library and sources are checked for changes.
+    pub fn with_interval(self, interval: Duration) -> Self {
+        Self { interval, ..self }
+    }
+}
+
+impl Backend {
+    /// Like [`Backend::run_ui`], but builds the UI from the library of `reload`, and rebuilds it every time the library changes.
+    pub fn run_hot(&self, reload: HotReload) {
+        let loaded = Rc::new(Loaded::default());
+        let tree = Rc::new(RefCell::new(None::<Element>));
+
+        if let Err(err) = loaded.load(&reload) {
+            error!("Failed to load {}: {err:?}", reload.library.to_string_lossy());
+        }
+        *tree.borrow_mut() = Some(loaded.build(self));
+
+        let sources_changed = Rc::new(Cell::new(reload.sources.as_ref().map(|(dir, _)| newest_modification(dir))));
+        let building = Arc::new(AtomicBool::new(false));
+        let backend = self.weak();
+
+        set_interval(reload.interval, move || {
+            let Some(backend) = backend.upgrade().map(|inner| Backend { inner }) else { return };
+
+            if let (Some((dir, command)), Some(previous)) = (&reload.sources, sources_changed.get()) {
+                let newest = newest_modification(dir);
+
+                if newest > previous && !building.swap(true, Ordering::SeqCst) {
+                    sources_changed.set(Some(newest));
+                    rebuild_library(command.clone(), building.clone());
+                }
+            }
+
+            if modified(&reload.library) <= loaded.modified.get() {
+                return;
+            }
+
+            let previous = loaded.build.get();
+            match loaded.load(&reload) {
+                Ok(()) => {
+                    info!("Reloading the UI...");
+                    reload_tree(&backend, &tree, &loaded, previous);
+                },
+                // Possibly still being written, so it's retried on the next check
+                Err(err) => warn!("Failed to reload {}: {err:?}", reload.library.to_string_lossy())
+            }
+        });
+
+        self.run_events();
+    }
+}
+
+
+#[derive(Debug)]
+enum LoadError {
+    Io(io::Error),
+    Library(libloading::Error)
+}
+
+/// The libraries loaded so far, which are never unloaded,
+/// as the closures and values they created may still be referenced by signals.
+#[derive(Default)]
+struct Loaded {
+    libraries: RefCell<Vec<Library>>,
+    build: Cell<Option<BuildUi>>,
+    modified: Cell<Option<SystemTime>>
+}
+
+impl Loaded {
+    fn load(&self, reload: &HotReload) -> Result<(), LoadError> {
+        let modified = modified(&reload.library);
+        let name = reload.library.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+
+        remove_copies(&name);
+
+        // Copied first, as most platforms return the already loaded library when loading the same path again
+        let copy = std::env::temp_dir().join(format!("{COPY_PREFIX}{}-{name}", fastrand::u64(..)));
+        fs::copy(&reload.library, &copy).map_err(LoadError::Io)?;
+
+        // The library is expected to export a BuildUi function under this symbol, built against the same lumi-dylib
+        let library = unsafe { Library::new(&copy) }.map_err(LoadError::Library)?;
+        let build = unsafe { library.get::<BuildUi>(reload.symbol.as_bytes()).map(|symbol| *symbol) }.map_err(LoadError::Library)?;
+
+        self.libraries.borrow_mut().push(library);
+        self.build.set(Some(build));
+        self.modified.set(modified);
+
+        Ok(())
+    }
+
+    fn build(&self, backend: &Backend) -> Element {
+        let root = ElementBuilder::root();
+
+        if let Some(build) = self.build.get() {
+            build(&root);
+        }
+
+        root.build(backend, None)
+    }
+}
+
+/// Destructs the current tree while keeping its windows, which the new tree takes over in order.
+/// If building it panics, the tree is built again with `previous`, so the panic doesn't escape the timer.
+fn reload_tree(backend: &Backend, tree: &RefCell<Option<Element>>, loaded: &Loaded, previous: Option<BuildUi>) {
+    {
+        let _reloading = Reloading::start();
+
+        if let Some(old) = tree.borrow_mut().take() {
+            old.destruct(backend);
+        }
+        let new = panic::catch_unwind(AssertUnwindSafe(|| loaded.build(backend))).unwrap_or_else(|_| {
+            error!("Building the reloaded UI panicked, building the previous one again.");
+            loaded.build.set(previous);
+            loaded.build(backend)
+        });
+        *tree.borrow_mut() = Some(new);
+    }
+
+    // Windows the new tree didn't take over
+    let leftover = crate::LOCAL_RETAINED_WINDOWS.with(|retained| std::mem::take(&mut *retained.borrow_mut()));
+    for window in leftover {
+        window.close(&backend.renderer_data());
+    }
+
+    // Nothing else triggers a redraw if the new tree looks different, but none of its signals changed
+    for window_id in backend.inner.windows.borrow().keys() {
+        crate::global_send(Event::Custom(CustomEvent::Redraw(window_id.clone())));
+    }
+}
+
+/// Marks a reload as in progress until dropped, so a panicking `BuildUi` doesn't leave it set.
+struct Reloading;
+
+impl Reloading {
+    fn start() -> Self {
+        crate::LOCAL_HOT_RELOADING.with(|reloading| reloading.set(true));
+        Self
+    }
+}
+
+impl Drop for Reloading {
+    fn drop(&mut self) {
+        crate::LOCAL_HOT_RELOADING.with(|reloading| reloading.set(false));
+    }
+}
+
+/// Whether windows should be kept for the next tree instead of closed, as a reload is in progress.
+pub(crate) fn is_reloading() -> bool {
+    crate::LOCAL_HOT_RELOADING.with(|reloading| reloading.get())
+}
+
+pub(crate) fn retain_window(window: WindowInner) {
+    crate::LOCAL_RETAINED_WINDOWS.with(|retained| retained.borrow_mut().push(window));
+}
+
+pub(crate) fn take_retained_window() -> Option<WindowInner> {
+    crate::LOCAL_RETAINED_WINDOWS.with(|retained| {
+        let mut retained = retained.borrow_mut();
+        (!retained.is_empty()).then(|| retained.remove(0))
+    })
+}
+
+fn rebuild_library(command: Vec<String>, building: Arc<AtomicBool>) {
+    info!("Sources changed, running {}...", command.join(" "));
+
+    thread::spawn(move || {
+        let Some((program, args)) = command.split_first() else { return };
+
+        match Command::new(program).args(args).status() {
+            Ok(status) if status.success() => info!("Rebuilt the UI library."),
+            Ok(status) => error!("Rebuilding the UI library failed with {status}"),
+            Err(err) => error!("Failed to run {program}: {err}")
+        }
+
+        building.store(false, Ordering::SeqCst);
+    });
+}
+
+const COPY_PREFIX: &str = "lumi-hot-";
+
+/// Removes the copies of the library called `name` made by earlier loads, or earlier runs.
+/// Loaded copies can't be removed on some platforms, which are left for a later load.
+fn remove_copies(name: &str) {
+    let Ok(entries) = fs::read_dir(std::env::temp_dir()) else { return };
+
+    for path in entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+        let is_copy = path.file_name()
+        .map(|file| file.to_string_lossy())
+        .is_some_and(|file| file.starts_with(COPY_PREFIX) && file.ends_with(&format!("-{name}")));
+
+        if is_copy {
+            fs::remove_file(&path).ok();
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// The most recent modification of any file in `dir`, or its subdirectories.
+fn newest_modification(dir: &Path) -> SystemTime {
+    fn walk(dir: &Path, newest: &mut SystemTime) -> io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+
+            if path.is_dir() {
+                walk(&path, newest)?;
+            } else if let Some(modified) = modified(&path) {
+                *newest = (*newest).max(modified);
+            }
+        }
+
+        Ok(())
+    }
+
+    let mut newest = SystemTime::UNIX_EPOCH;
+    if let Err(err) = walk(dir, &mut newest) {
+        warn!("Failed to check {} for changes: {err}", dir.to_string_lossy());
+    }
+
+    newest
+}
@@ -0,0 +1,172 @@
+use crate::geometry::Bounds;
+
+use super::style::LayoutStyle;
+
+
+/// Positions a widget relative to the bounds of its parent element instead of the window:
+/// a widget's own bounds, a window's size, or the area of a clip. Other elements pass their parent's area on.
+///
+/// Offsets are in pixels from the parent's edges, pointing inwards. Along each axis, a start anchor
+/// (`left`/`top`) takes precedence over an end anchor, which takes precedence over the center offset.
+/// Without any of those, the widget's own `x`/`y` are used as offsets from the parent's top left corner.
+///
+/// The size the anchors give a widget is still constrained by the min and max sizes of its [`LayoutStyle`].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Anchors {
+    pub left: Option<i32>,
+    pub right: Option<i32>,
+    pub top: Option<i32>,
+    pub bottom: Option<i32>,
+    /// Offset of the widget's center from the parent's center.
+    pub center_x: Option<i32>,
+    pub center_y: Option<i32>,
+    /// Overrides the widget's own width. Without it, anchoring both `left` and `right` stretches the widget between them.
+    pub width: Option<Size>,
+    pub height: Option<Size>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Size {
+    Pixels(u32),
+    /// Percent of the parent's size, from 0.0 to 100.0.
+    Percent(f32)
+}
+
+/// The anchors along one axis.
+struct AxisAnchors {
+    start: Option<i32>,
+    end: Option<i32>,
+    center: Option<i32>,
+    size: Option<Size>
+}
+
+
+impl Anchors {
+    /// Fills the parent entirely.
+    pub fn fill() -> Self {
+        Self { left: Some(0), right: Some(0), top: Some(0), bottom: Some(0), ..Default::default() }
+    }
+
+    /// Centers the widget in its parent, keeping its size.
+    pub fn centered() -> Self {
+        Self { center_x: Some(0), center_y: Some(0), ..Default::default() }
+    }
+
+    pub fn with_left(self, left: i32) -> Self {
+        Self { left: Some(left), ..self }
+    }
+
+    pub fn with_right(self, right: i32) -> Self {
+        Self { right: Some(right), ..self }
+    }
+
+    pub fn with_top(self, top: i32) -> Self {
+        Self { top: Some(top), ..self }
+    }
+
+    pub fn with_bottom(self, bottom: i32) -> Self {
+        Self { bottom: Some(bottom), ..self }
+    }
+
+    pub fn with_center(self, x: i32, y: i32) -> Self {
+        Self { center_x: Some(x), center_y: Some(y), ..self }
+    }
+
+    pub fn with_width(self, width: Size) -> Self {
+        Self { width: Some(width), ..self }
+    }
+
+    pub fn with_height(self, height: Size) -> Self {
+        Self { height: Some(height), ..self }
+    }
+
+    /// The border box of a widget with the geometry `own` inside of `parent`, sized within the constraints of `style`.
+    pub fn resolve(&self, parent: Bounds, own: Bounds, style: &LayoutStyle) -> Bounds {
+        let horizontal = AxisAnchors {
+            start: self.left,
+            end: self.right,
+            center: self.center_x,
+            size: self.width
+        };
+        let vertical = AxisAnchors {
+            start: self.top,
+            end: self.bottom,
+            center: self.center_y,
+            size: self.height
+        };
+
+        // Constrained before positioning, so end and center anchors place the final size
+        let (width, height) = style.constrain(
+            horizontal.size(parent.width, own.width),
+            vertical.size(parent.height, own.height)
+        );
+        let x = horizontal.position(parent.x, parent.width, own.x, width);
+        let y = vertical.position(parent.y, parent.height, own.y, height);
+
+        Bounds::new(x, y, width, height)
+    }
+}
+
+impl AxisAnchors {
+    /// The size along this axis, which stretching never makes negative.
+    fn size(&self, parent_size: u32, own_size: u32) -> u32 {
+        let parent = parent_size as f32;
+
+        let size = match (self.size, self.start, self.end) {
+            (Some(Size::Pixels(pixels)), _, _) => pixels as f32,
+            (Some(Size::Percent(percent)), _, _) => parent * percent / 100.0,
+            (None, Some(start), Some(end)) => parent - start as f32 - end as f32,
+            (None, _, _) => own_size as f32
+        };
+
+        size.max(0.0).round() as u32
+    }
+
+    fn position(&self, parent_start: i32, parent_size: u32, own_offset: i32, size: u32) -> i32 {
+        let (parent, size) = (parent_size as f32, size as f32);
+
+        let offset = if let Some(start) = self.start {
+            start as f32
+        } else if let Some(end) = self.end {
+            parent - end as f32 - size
+        } else if let Some(center) = self.center {
+            (parent - size) / 2.0 + center as f32
+        } else {
+            own_offset as f32
+        };
+
+        parent_start + offset.round() as i32
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::{geometry::Bounds, layout::style::LayoutStyle};
+
+    use super::{Anchors, Size};
+
+    const PARENT: Bounds = Bounds::new(10, 20, 200, 100);
+    const OWN: Bounds = Bounds::new(5, 6, 40, 30);
+
+    #[test]
+    fn start_anchors_take_precedence() {
+        assert_eq!(Anchors::default().resolve(PARENT, OWN, &LayoutStyle::default()), Bounds::new(15, 26, 40, 30));
+        assert_eq!(Anchors::default().with_right(10).with_bottom(5).resolve(PARENT, OWN, &LayoutStyle::default()), Bounds::new(160, 85, 40, 30));
+        assert_eq!(Anchors::centered().with_left(3).resolve(PARENT, OWN, &LayoutStyle::default()), Bounds::new(13, 55, 40, 30));
+        assert_eq!(Anchors::centered().with_center(-10, 0).resolve(PARENT, OWN, &LayoutStyle::default()), Bounds::new(80, 55, 40, 30));
+    }
+
+    #[test]
+    fn sizes_from_anchors_and_parent() {
+        assert_eq!(Anchors::fill().resolve(PARENT, OWN, &LayoutStyle::default()), PARENT);
+        assert_eq!(Anchors::fill().with_left(20).resolve(PARENT, OWN, &LayoutStyle::default().with_max_size(150, 50)), Bounds::new(30, 20, 150, 50));
+        assert_eq!(
+            Anchors::default().with_right(0).with_width(Size::Percent(25.0)).with_height(Size::Pixels(10)).resolve(PARENT, OWN, &LayoutStyle::default()),
+            Bounds::new(160, 26, 50, 10)
+        );
+        // End anchors place the constrained size, and stretching never goes below 0
+        assert_eq!(Anchors::default().with_right(10).resolve(PARENT, OWN, &LayoutStyle::default().with_min_size(60, 0)), Bounds::new(140, 26, 60, 30));
+        assert_eq!(Anchors::fill().with_left(150).with_right(150).resolve(PARENT, OWN, &LayoutStyle::default()).width, 0);
+    }
+}
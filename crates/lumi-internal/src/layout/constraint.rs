@@ -0,0 +1,269 @@
+use std::{cell::RefCell, collections::{BTreeSet, HashMap}, fmt::Debug, rc::Rc};
+
+use cassowary::{AddConstraintError, Solver, WeightedRelation::GE};
+use log::warn;
+
+use crate::{geometry::Bounds, signals::{Signal, SignalTrait}};
+
+use super::LayoutSlot;
+
+pub use cassowary::{self, strength::{REQUIRED, STRONG, MEDIUM, WEAK}, Constraint, Expression, Variable, WeightedRelation};
+
+
+/// The area a constraint layout solves in, usually following the window's dimensions.
+#[derive(Debug, Default, Clone)]
+pub struct ConstraintBuilder {
+    pub x: Signal<i32>,
+    pub y: Signal<i32>,
+    pub width: Signal<u32>,
+    pub height: Signal<u32>
+}
+
+/// Solves the geometry of its items from linear constraints between them, using the Cassowary algorithm.
+/// Changes to the container are suggested to the solver, which only updates the items whose geometry changed.
+///
+/// The container's geometry is suggested with `STRONG` strength, so preferences that should give way
+/// when the container is too small belong at `MEDIUM` or below. `STRONG` ones may resize the container instead.
+///
+/// ```ignore
+/// sidebar.right() |EQ(REQUIRED)| content.left - 8.0
+/// content.width |GE(MEDIUM)| 300.0
+/// ```
+#[derive(Clone)]
+pub struct ConstraintLayout {
+    inner: Rc<ConstraintLayoutInner>
+}
+
+/// An item of a constraint layout, whose variables are used in constraints.
+#[derive(Debug, Clone)]
+pub struct ConstraintItem {
+    pub left: Variable,
+    pub top: Variable,
+    pub width: Variable,
+    pub height: Variable,
+    slot: LayoutSlot
+}
+
+struct ConstraintLayoutInner {
+    solver: RefCell<Solver>,
+    container: ConstraintItem,
+    items: RefCell<Vec<ConstraintItem>>,
+    /// The index of the item each variable belongs to, with the container at 0
+    owners: RefCell<HashMap<Variable, usize>>
+}
+
+
+impl ConstraintBuilder {
+    /// Creates the layout, to which items are added with [`ConstraintLayout::item`].
+    pub fn build(&self) -> ConstraintLayout {
+        let container = ConstraintItem::new();
+        let mut solver = Solver::new();
+
+        // The container's geometry comes from its signals, which only the solver may adjust if nothing else fits
+        for variable in container.variables() {
+            solver.add_edit_variable(variable, STRONG).expect("Container variables are only added once");
+        }
+
+        let layout = ConstraintLayout {
+            inner: Rc::new(ConstraintLayoutInner {
+                solver: RefCell::new(solver),
+                owners: RefCell::new(container.variables().map(|variable| (variable, 0)).collect()),
+                items: RefCell::new(vec![container.clone()]),
+                container
+            })
+        };
+
+        let inner = layout.inner.clone();
+        let signals = (self.x.clone(), self.y.clone(), self.width.clone(), self.height.clone());
+        let cloned = signals.clone();
+        signals.notify(move || inner.suggest(&cloned));
+
+        layout.inner.suggest(&signals);
+        layout
+    }
+}
+
+impl ConstraintLayout {
+    /// The container's own variables, to constrain items against.
+    pub fn container(&self) -> &ConstraintItem {
+        &self.inner.container
+    }
+
+    /// Adds an item with a width and height of at least 0, but otherwise unconstrained.
+    pub fn item(&self) -> ConstraintItem {
+        let item = ConstraintItem::new();
+        let index = self.inner.items.borrow().len();
+
+        self.inner.owners.borrow_mut().extend(item.variables().map(|variable| (variable, index)));
+        self.inner.items.borrow_mut().push(item.clone());
+
+        self.add_all([
+            item.width |GE(REQUIRED)| 0.0,
+            item.height |GE(REQUIRED)| 0.0
+        ]);
+
+        item
+    }
+
+    /// Adds a constraint, and updates every item it moved.
+    /// Required constraints which conflict with the previous ones are left out with a warning.
+    pub fn add(&self, constraint: Constraint) {
+        self.add_all([constraint]);
+    }
+
+    pub fn add_all(&self, constraints: impl IntoIterator<Item = Constraint>) {
+        {
+            let mut solver = self.inner.solver.borrow_mut();
+
+            for constraint in constraints {
+                match solver.add_constraint(constraint) {
+                    Ok(()) => {},
+                    Err(AddConstraintError::UnsatisfiableConstraint) => warn!("Ignoring a required layout constraint that can't be satisfied!"),
+                    Err(err) => warn!("Failed to add a layout constraint: {err:?}")
+                }
+            }
+        }
+
+        self.inner.update();
+    }
+}
+
+impl Debug for ConstraintLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConstraintLayout")
+        .field("container", &self.inner.container)
+        .field("items", &(self.inner.items.borrow().len() - 1))
+        .finish_non_exhaustive()
+    }
+}
+
+impl ConstraintItem {
+    fn new() -> Self {
+        Self {
+            left: Variable::new(),
+            top: Variable::new(),
+            width: Variable::new(),
+            height: Variable::new(),
+            slot: LayoutSlot::new(Signal::new(Bounds::default()))
+        }
+    }
+
+    pub fn right(&self) -> Expression {
+        self.left + self.width
+    }
+
+    pub fn bottom(&self) -> Expression {
+        self.top + self.height
+    }
+
+    pub fn center_x(&self) -> Expression {
+        self.left + self.width * 0.5
+    }
+
+    pub fn center_y(&self) -> Expression {
+        self.top + self.height * 0.5
+    }
+
+    /// Where the widget for this item should be placed, updated whenever the solution changes.
+    pub fn slot(&self) -> &LayoutSlot {
+        &self.slot
+    }
+
+    fn variables(&self) -> impl Iterator<Item = Variable> {
+        [self.left, self.top, self.width, self.height].into_iter()
+    }
+}
+
+impl ConstraintLayoutInner {
+    fn suggest(&self, (x, y, width, height): &(Signal<i32>, Signal<i32>, Signal<u32>, Signal<u32>)) {
+        let container = &self.container;
+        let values = [
+            (container.left, *x.get() as f64),
+            (container.top, *y.get() as f64),
+            (container.width, *width.get() as f64),
+            (container.height, *height.get() as f64)
+        ];
+
+        {
+            let mut solver = self.solver.borrow_mut();
+            for (variable, value) in values {
+                if let Err(err) = solver.suggest_value(variable, value) {
+                    warn!("Failed to suggest the container's geometry: {err:?}");
+                }
+            }
+        }
+
+        self.update();
+    }
+
+    /// Moves the items whose variables changed since the last update.
+    fn update(&self) {
+        let updated = {
+            let mut solver = self.solver.borrow_mut();
+            let owners = self.owners.borrow();
+            let items = self.items.borrow();
+
+            let changed = solver.fetch_changes().iter()
+            .filter_map(|(variable, _)| owners.get(variable).copied())
+            .collect::<BTreeSet<_>>();
+
+            changed.into_iter().map(|index| {
+                let item = &items[index];
+                let value = |variable| solver.get_value(variable).round();
+
+                let bounds = Bounds::new(
+                    value(item.left) as i32,
+                    value(item.top) as i32,
+                    value(item.width).max(0.0) as u32,
+                    value(item.height).max(0.0) as u32
+                );
+
+                (item.slot.bounds.clone(), bounds)
+            }).collect::<Vec<_>>()
+        };
+
+        // Set once the solver is released, as subscribers may add constraints of their own
+        for (signal, bounds) in updated {
+            if *signal.get() != bounds {
+                signal.set(bounds);
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::{geometry::Bounds, signals::{Signal, SignalTrait}};
+
+    use super::{ConstraintBuilder, WeightedRelation::{EQ, GE}, MEDIUM, REQUIRED};
+
+    #[test]
+    fn resolves_when_the_container_resizes() {
+        let width = Signal::new(800);
+        let layout = ConstraintBuilder { width: width.clone(), height: Signal::new(600), ..Default::default() }.build();
+        let (container, sidebar, content) = (layout.container().clone(), layout.item(), layout.item());
+
+        layout.add_all([
+            sidebar.left |EQ(REQUIRED)| container.left,
+            sidebar.width |EQ(REQUIRED)| 200.0,
+            sidebar.right() |EQ(REQUIRED)| (content.left - 8.0),
+            content.right() |EQ(REQUIRED)| container.right(),
+            content.width |GE(MEDIUM)| 300.0
+        ]);
+        for item in [&sidebar, &content] {
+            layout.add_all([item.top |EQ(REQUIRED)| container.top, item.height |EQ(REQUIRED)| container.height]);
+        }
+
+        assert_eq!(*sidebar.slot().bounds.get(), Bounds::new(0, 0, 200, 600));
+        assert_eq!(*content.slot().bounds.get(), Bounds::new(208, 0, 592, 600));
+
+        width.set(1000);
+        assert_eq!(*content.slot().bounds.get(), Bounds::new(208, 0, 792, 600));
+
+        // The container's size wins over the content's preferred minimum
+        width.set(400);
+        assert_eq!(*container.slot().bounds.get(), Bounds::new(0, 0, 400, 600));
+        assert_eq!(*content.slot().bounds.get(), Bounds::new(208, 0, 192, 600));
+    }
+}
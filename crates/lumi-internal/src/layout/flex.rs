@@ -0,0 +1,435 @@
+use std::{cell::RefCell, fmt::Debug, ops::Range, rc::Rc};
+
+use crate::{geometry::Bounds, signals::{Signal, SignalTrait}, widgets::text::TextBuilder};
+
+use super::{style::{Edges, LayoutStyle}, LayoutSlot};
+
+
+/// How the items of a line are placed along the main axis, when they don't fill it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MainAlign {
+    #[default]
+    Start,
+    End,
+    Center,
+    /// No space before the first and after the last item.
+    SpaceBetween,
+    /// Half as much space before the first and after the last item as between items.
+    SpaceAround,
+    SpaceEvenly
+}
+
+/// How items are placed across the main axis, inside of their line.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CrossAlign {
+    #[default]
+    Start,
+    End,
+    Center,
+    /// Items are as large as their line, ignoring their own cross size.
+    Stretch
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Axis {
+    Horizontal,
+    Vertical
+}
+
+/// A child of a row or column, with the size it would like to have.
+#[derive(Debug, Clone)]
+pub struct FlexItem {
+    pub width: Signal<u32>,
+    pub height: Signal<u32>,
+    /// The share of the leftover space this item grows by, 0.0 keeps it at its size.
+    pub grow: Signal<f32>,
+    /// How much this item shrinks when the items don't fit, relative to the others and weighted by their sizes.
+    pub shrink: Signal<f32>,
+    /// The margin is kept free around the item, and its min/max sizes are applied after growing or shrinking.
+    pub style: Signal<LayoutStyle>
+}
+
+macro_rules! flex_builder {
+    ($name:ident, $axis:expr, $doc:literal) => {
+        #[doc = $doc]
+        #[derive(Debug, Default, Clone)]
+        pub struct $name {
+            pub x: Signal<i32>,
+            pub y: Signal<i32>,
+            pub width: Signal<u32>,
+            pub height: Signal<u32>,
+            /// Space between items, and between lines when wrapping.
+            pub gap: Signal<u32>,
+            pub padding: Signal<Edges>,
+            pub main_align: Signal<MainAlign>,
+            pub cross_align: Signal<CrossAlign>,
+            /// Moves items which don't fit anymore to a new line, instead of shrinking them.
+            pub wrap: Signal<bool>
+        }
+
+        impl $name {
+            /// Creates the layout, to which the children are added with [`FlexLayout::item`].
+            pub fn build(&self) -> FlexLayout {
+                FlexLayout::new($axis, Container {
+                    x: self.x.clone(),
+                    y: self.y.clone(),
+                    width: self.width.clone(),
+                    height: self.height.clone(),
+                    gap: self.gap.clone(),
+                    padding: self.padding.clone(),
+                    main_align: self.main_align.clone(),
+                    cross_align: self.cross_align.clone(),
+                    wrap: self.wrap.clone()
+                })
+            }
+        }
+    };
+}
+
+flex_builder!(RowBuilder, Axis::Horizontal, "Lays out its items from left to right.");
+flex_builder!(ColumnBuilder, Axis::Vertical, "Lays out its items from top to bottom.");
+
+/// The positions and sizes of the items of a row or column,
+/// computed again whenever the container or one of the items changes, e.g. when the window is resized.
+#[derive(Clone)]
+pub struct FlexLayout {
+    inner: Rc<FlexLayoutInner>
+}
+
+struct FlexLayoutInner {
+    axis: Axis,
+    container: Container,
+    items: RefCell<Vec<FlexItem>>,
+    layout: Signal<Vec<Bounds>>
+}
+
+#[derive(Debug, Clone)]
+struct Container {
+    x: Signal<i32>,
+    y: Signal<i32>,
+    width: Signal<u32>,
+    height: Signal<u32>,
+    gap: Signal<u32>,
+    padding: Signal<Edges>,
+    main_align: Signal<MainAlign>,
+    cross_align: Signal<CrossAlign>,
+    wrap: Signal<bool>
+}
+
+/// An item's size along the main and cross axis, before growing or shrinking.
+#[derive(Debug, Clone, Copy)]
+struct Basis {
+    main: f32,
+    cross: f32,
+    grow: f32,
+    shrink: f32,
+    style: LayoutStyle
+}
+
+
+impl FlexItem {
+    pub fn new(width: Signal<u32>, height: Signal<u32>) -> Self {
+        Self {
+            width,
+            height,
+            ..Default::default()
+        }
+    }
+
+    /// An item as wide as `text` wraps at, and as high as it measured.
+    pub fn text(text: &TextBuilder) -> Self {
+        Self::new(text.width.clone(), text.outer_height()).with_style(text.style.clone())
+    }
+
+    pub fn with_grow(self, grow: Signal<f32>) -> Self {
+        Self { grow, ..self }
+    }
+
+    pub fn with_shrink(self, shrink: Signal<f32>) -> Self {
+        Self { shrink, ..self }
+    }
+
+    /// Should be the same style the widget placed in this item's slot uses.
+    pub fn with_style(self, style: Signal<LayoutStyle>) -> Self {
+        Self { style, ..self }
+    }
+}
+
+impl Default for FlexItem {
+    fn default() -> Self {
+        Self {
+            width: Signal::default(),
+            height: Signal::default(),
+            grow: Signal::new(0.0),
+            shrink: Signal::new(1.0),
+            style: Signal::default()
+        }
+    }
+}
+
+impl FlexLayout {
+    fn new(axis: Axis, container: Container) -> Self {
+        let layout = Self {
+            inner: Rc::new(FlexLayoutInner {
+                axis,
+                container,
+                items: RefCell::new(Vec::new()),
+                layout: Signal::new(Vec::new())
+            })
+        };
+
+        let c = &layout.inner.container;
+        let inner = layout.inner.clone();
+        (c.x.clone(), c.y.clone(), c.width.clone(), c.height.clone(), c.gap.clone(), c.padding.clone(), c.main_align.clone(), c.cross_align.clone(), c.wrap.clone())
+        .notify(move || inner.relayout());
+
+        layout
+    }
+
+    /// Adds an item after the previous ones, returning where it should be placed.
+    pub fn item(&self, item: FlexItem) -> LayoutSlot {
+        let index = self.inner.items.borrow().len();
+
+        // The layout lives as long as its items and container, as there is no way to unsubscribe
+        let inner = self.inner.clone();
+        (item.width.clone(), item.height.clone(), item.grow.clone(), item.shrink.clone(), item.style.clone())
+        .notify(move || inner.relayout());
+
+        self.inner.items.borrow_mut().push(item);
+        self.inner.relayout();
+
+        LayoutSlot::new(self.inner.layout.relative(move |layout| layout.get(index).copied().unwrap_or_default()))
+    }
+
+    /// The bounds of every item, in the order they were added.
+    pub fn bounds(&self) -> &Signal<Vec<Bounds>> {
+        &self.inner.layout
+    }
+}
+
+impl Debug for FlexLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FlexLayout")
+        .field("axis", &self.inner.axis)
+        .field("items", &self.inner.items.borrow().len())
+        .finish_non_exhaustive()
+    }
+}
+
+impl FlexLayoutInner {
+    fn relayout(&self) {
+        let layout = self.compute();
+
+        if *self.layout.get() != layout {
+            self.layout.set(layout);
+        }
+    }
+
+    fn compute(&self) -> Vec<Bounds> {
+        let c = &self.container;
+        let gap = *c.gap.get() as f32;
+        let (main_align, cross_align, wrap) = (*c.main_align.get(), *c.cross_align.get(), *c.wrap.get());
+
+        let area = c.padding.get().inset(Bounds::new(*c.x.get(), *c.y.get(), *c.width.get(), *c.height.get()));
+        let (main_size, cross_size) = self.axis.split(area.width as f32, area.height as f32);
+
+        let items = self.items.borrow().iter().map(|item| {
+            let style = *item.style.get();
+            let (width, height) = style.outer_size(*item.width.get(), *item.height.get());
+            let (main, cross) = self.axis.split(width as f32, height as f32);
+
+            Basis { main, cross, grow: item.grow.get().max(0.0), shrink: item.shrink.get().max(0.0), style }
+        }).collect::<Vec<_>>();
+
+        // Without wrapping, everything fits into a single line
+        let lines = wrap_lines(&items, if wrap { main_size } else { f32::INFINITY }, gap);
+
+        let mut layout = vec![Bounds::default(); items.len()];
+        let mut line_start = 0.0;
+
+        for line in lines.iter().cloned() {
+            let line_items = &items[line.clone()];
+
+            // A single line fills the container, wrapped lines are as large as their largest item
+            let line_cross = if lines.len() == 1 {
+                cross_size
+            } else {
+                line_items.iter().map(|item| item.cross).fold(0.0, f32::max)
+            };
+
+            let sizes = flex_sizes(line_items, main_size, gap);
+            let leftover = (main_size - sizes.iter().sum::<f32>() - gap * line_items.len().saturating_sub(1) as f32).max(0.0);
+            let (mut cursor, spacing) = main_align.distribute(leftover, line_items.len());
+
+            for ((index, item), size) in line.zip(line_items).zip(sizes) {
+                let (cross_offset, cross) = cross_align.place(item.cross, line_cross);
+                let ((x, width), (y, height)) = self.axis.join((cursor, size), (line_start + cross_offset, cross));
+
+                layout[index] = item.style.from_outer(Bounds::new(
+                    area.x + x.round() as i32,
+                    area.y + y.round() as i32,
+                    width.round().max(0.0) as u32,
+                    height.round().max(0.0) as u32
+                ));
+
+                cursor += size + gap + spacing;
+            }
+
+            line_start += line_cross + gap;
+        }
+
+        layout
+    }
+}
+
+impl Axis {
+    /// Turns a width and height into main and cross sizes.
+    fn split(self, width: f32, height: f32) -> (f32, f32) {
+        match self {
+            Axis::Horizontal => (width, height),
+            Axis::Vertical => (height, width)
+        }
+    }
+
+    /// Turns the (position, size) along the main and cross axis into the (x, width) and (y, height).
+    fn join(self, main: (f32, f32), cross: (f32, f32)) -> ((f32, f32), (f32, f32)) {
+        match self {
+            Axis::Horizontal => (main, cross),
+            Axis::Vertical => (cross, main)
+        }
+    }
+}
+
+impl MainAlign {
+    /// The offset of the first item and the extra space between items, for `leftover` free space.
+    fn distribute(self, leftover: f32, count: usize) -> (f32, f32) {
+        let count = count as f32;
+
+        match self {
+            MainAlign::Start => (0.0, 0.0),
+            MainAlign::End => (leftover, 0.0),
+            MainAlign::Center => (leftover / 2.0, 0.0),
+            MainAlign::SpaceBetween if count > 1.0 => (0.0, leftover / (count - 1.0)),
+            MainAlign::SpaceBetween => (0.0, 0.0),
+            MainAlign::SpaceAround if count > 0.0 => (leftover / count / 2.0, leftover / count),
+            MainAlign::SpaceAround => (0.0, 0.0),
+            MainAlign::SpaceEvenly => (leftover / (count + 1.0), leftover / (count + 1.0))
+        }
+    }
+}
+
+impl CrossAlign {
+    /// The offset and size of an item inside of its line.
+    pub(super) fn place(self, size: f32, line: f32) -> (f32, f32) {
+        match self {
+            CrossAlign::Start => (0.0, size),
+            CrossAlign::End => (line - size, size),
+            CrossAlign::Center => ((line - size) / 2.0, size),
+            CrossAlign::Stretch => (0.0, line)
+        }
+    }
+}
+
+/// Splits the items into lines that fit into `main_size`, each holding at least one item.
+fn wrap_lines(items: &[Basis], main_size: f32, gap: f32) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let (mut start, mut used) = (0, 0.0);
+
+    for (index, item) in items.iter().enumerate() {
+        if index > start && used + gap + item.main > main_size {
+            lines.push(start..index);
+            (start, used) = (index, 0.0);
+        }
+
+        used += if index > start { gap + item.main } else { item.main };
+    }
+
+    lines.push(start..items.len());
+    lines
+}
+
+/// The main sizes of the items of a line, after growing into the free space or shrinking to fit.
+fn flex_sizes(items: &[Basis], main_size: f32, gap: f32) -> Vec<f32> {
+    let used = items.iter().map(|item| item.main).sum::<f32>() + gap * items.len().saturating_sub(1) as f32;
+    let free = main_size - used;
+
+    let total_grow = items.iter().map(|item| item.grow).sum::<f32>();
+    let total_shrink = items.iter().map(|item| item.shrink * item.main).sum::<f32>();
+
+    items.iter().map(|item| {
+        if free > 0.0 && total_grow > 0.0 {
+            item.main + free * item.grow / total_grow
+        } else if free < 0.0 && total_shrink > 0.0 {
+            (item.main + free * item.shrink * item.main / total_shrink).max(0.0)
+        } else {
+            item.main
+        }
+    }).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::{geometry::Bounds, layout::style::{Edges, LayoutStyle}, signals::{Signal, SignalTrait}};
+
+    use super::{flex_sizes, wrap_lines, Basis, ColumnBuilder, FlexItem, RowBuilder};
+
+    fn basis(main: f32, grow: f32, shrink: f32) -> Basis {
+        Basis { main, cross: 0.0, grow, shrink, style: LayoutStyle::default() }
+    }
+
+    fn item(width: u32, height: u32) -> FlexItem {
+        FlexItem::new(Signal::new(width), Signal::new(height))
+    }
+
+    #[test]
+    fn grows_and_shrinks_by_share() {
+        assert_eq!(flex_sizes(&[basis(40.0, 1.0, 1.0), basis(60.0, 3.0, 1.0)], 190.0, 10.0), vec![60.0, 120.0]);
+        // Shrinking is weighted by the items' sizes
+        assert_eq!(flex_sizes(&[basis(100.0, 0.0, 1.0), basis(50.0, 0.0, 1.0)], 120.0, 0.0), vec![80.0, 40.0]);
+        assert_eq!(flex_sizes(&[basis(100.0, 0.0, 0.0), basis(50.0, 0.0, 2.0)], 120.0, 0.0), vec![100.0, 20.0]);
+    }
+
+    #[test]
+    fn wraps_items_that_dont_fit() {
+        let items = [basis(50.0, 0.0, 1.0), basis(50.0, 0.0, 1.0), basis(50.0, 0.0, 1.0)];
+
+        assert_eq!(wrap_lines(&items, 120.0, 10.0), vec![0..2, 2..3]);
+        // Every line holds at least one item, even if it is too large
+        assert_eq!(wrap_lines(&items[..1], 20.0, 10.0), vec![0..1]);
+    }
+
+    #[test]
+    fn row_relayouts_when_resized() {
+        let row = RowBuilder {
+            width: Signal::new(200),
+            height: Signal::new(50),
+            gap: Signal::new(10),
+            padding: Signal::new(Edges::uniform(5)),
+            ..Default::default()
+        };
+        let layout = row.build();
+        layout.item(item(40, 20).with_grow(Signal::new(1.0)));
+        layout.item(item(60, 20).with_grow(Signal::new(3.0)));
+
+        assert_eq!(*layout.bounds().get(), vec![Bounds::new(5, 5, 60, 20), Bounds::new(75, 5, 120, 20)]);
+
+        row.width.set(100);
+        assert_eq!(*layout.bounds().get(), vec![Bounds::new(5, 5, 32, 20), Bounds::new(47, 5, 48, 20)]);
+    }
+
+    #[test]
+    fn wrapped_column_places_lines_side_by_side() {
+        let column = ColumnBuilder {
+            width: Signal::new(100),
+            height: Signal::new(100),
+            wrap: Signal::new(true),
+            ..Default::default()
+        };
+        let layout = column.build();
+        layout.item(item(10, 60));
+        layout.item(item(20, 60).with_style(Signal::new(LayoutStyle::default().with_margin(Edges::uniform(2)))));
+
+        assert_eq!(*layout.bounds().get(), vec![Bounds::new(0, 0, 10, 60), Bounds::new(10, 0, 20, 60)]);
+    }
+}
@@ -0,0 +1,375 @@
+use std::{cell::RefCell, fmt::Debug, rc::Rc};
+
+use crate::{geometry::Bounds, signals::{Signal, SignalTrait}, widgets::text::TextBuilder};
+
+use super::{flex::CrossAlign, style::{Edges, LayoutStyle}, LayoutSlot};
+
+
+/// The size of a row or column of a grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Track {
+    /// Exactly this many pixels.
+    Fixed(u32),
+    /// A share of the space left over by the other tracks, like `fr` in CSS.
+    Fraction(f32),
+    /// As large as the largest item in it.
+    Auto,
+    /// As large as the largest item in it, but at least `min` and at most `max` pixels.
+    /// Grows towards `max` into leftover space before fractional tracks get any.
+    MinMax(u32, u32)
+}
+
+#[derive(Debug, Clone)]
+pub struct GridBuilder {
+    pub x: Signal<i32>,
+    pub y: Signal<i32>,
+    pub width: Signal<u32>,
+    pub height: Signal<u32>,
+    pub columns: Signal<Vec<Track>>,
+    /// Rows for items placed below these are added as [`Track::Auto`].
+    pub rows: Signal<Vec<Track>>,
+    pub column_gap: Signal<u32>,
+    pub row_gap: Signal<u32>,
+    pub padding: Signal<Edges>,
+    /// How items are placed horizontally inside of their cells.
+    pub justify_items: Signal<CrossAlign>,
+    /// How items are placed vertically inside of their cells.
+    pub align_items: Signal<CrossAlign>
+}
+
+/// A child of a grid, placed in the cell at `column` and `row` (starting at 0) and spanning as many tracks as given.
+#[derive(Debug, Clone)]
+pub struct GridItem {
+    pub column: Signal<usize>,
+    pub row: Signal<usize>,
+    pub column_span: Signal<usize>,
+    pub row_span: Signal<usize>,
+    /// The size the item would like to have, used by auto tracks and when it isn't stretched.
+    /// Items spanning several tracks don't size auto tracks.
+    pub width: Signal<u32>,
+    pub height: Signal<u32>,
+    /// Like for [`FlexItem`](super::flex::FlexItem), the margin is kept free inside of the cell.
+    pub style: Signal<LayoutStyle>
+}
+
+/// The positions and sizes of the items of a grid,
+/// computed again whenever the grid or one of the items changes.
+#[derive(Clone)]
+pub struct GridLayout {
+    inner: Rc<GridLayoutInner>
+}
+
+struct GridLayoutInner {
+    grid: GridBuilder,
+    items: RefCell<Vec<GridItem>>,
+    layout: Signal<Vec<Bounds>>
+}
+
+/// An item's cells and size, read from its signals.
+#[derive(Debug, Clone, Copy)]
+struct Placement {
+    column: usize,
+    row: usize,
+    column_span: usize,
+    row_span: usize,
+    width: f32,
+    height: f32,
+    style: LayoutStyle
+}
+
+
+impl Default for GridBuilder {
+    fn default() -> Self {
+        Self {
+            x: Signal::default(),
+            y: Signal::default(),
+            width: Signal::default(),
+            height: Signal::default(),
+            columns: Signal::default(),
+            rows: Signal::default(),
+            column_gap: Signal::default(),
+            row_gap: Signal::default(),
+            padding: Signal::default(),
+            justify_items: Signal::new(CrossAlign::Stretch),
+            align_items: Signal::new(CrossAlign::Stretch)
+        }
+    }
+}
+
+impl GridBuilder {
+    /// Creates the layout, to which the children are added with [`GridLayout::item`].
+    pub fn build(&self) -> GridLayout {
+        let layout = GridLayout {
+            inner: Rc::new(GridLayoutInner {
+                grid: self.clone(),
+                items: RefCell::new(Vec::new()),
+                layout: Signal::new(Vec::new())
+            })
+        };
+
+        let inner = layout.inner.clone();
+        (self.x.clone(), self.y.clone(), self.width.clone(), self.height.clone(), self.columns.clone(), self.rows.clone())
+        .notify(move || inner.relayout());
+
+        let inner = layout.inner.clone();
+        (self.column_gap.clone(), self.row_gap.clone(), self.padding.clone(), self.justify_items.clone(), self.align_items.clone())
+        .notify(move || inner.relayout());
+
+        layout
+    }
+}
+
+impl GridItem {
+    /// An item in a single cell.
+    pub fn new(column: usize, row: usize) -> Self {
+        Self {
+            column: Signal::new(column),
+            row: Signal::new(row),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_span(self, columns: usize, rows: usize) -> Self {
+        Self { column_span: Signal::new(columns), row_span: Signal::new(rows), ..self }
+    }
+
+    pub fn with_size(self, width: Signal<u32>, height: Signal<u32>) -> Self {
+        Self { width, height, ..self }
+    }
+
+    /// Sizes the item like [`FlexItem::text`](super::flex::FlexItem::text).
+    pub fn with_text_size(self, text: &TextBuilder) -> Self {
+        self.with_size(text.width.clone(), text.outer_height()).with_style(text.style.clone())
+    }
+
+    pub fn with_style(self, style: Signal<LayoutStyle>) -> Self {
+        Self { style, ..self }
+    }
+}
+
+impl Default for GridItem {
+    fn default() -> Self {
+        Self {
+            column: Signal::default(),
+            row: Signal::default(),
+            column_span: Signal::new(1),
+            row_span: Signal::new(1),
+            width: Signal::default(),
+            height: Signal::default(),
+            style: Signal::default()
+        }
+    }
+}
+
+impl GridLayout {
+    /// Adds an item to the grid, returning where it should be placed.
+    pub fn item(&self, item: GridItem) -> LayoutSlot {
+        let index = self.inner.items.borrow().len();
+
+        // Like flex layouts, the grid lives as long as its items
+        let inner = self.inner.clone();
+        (item.column.clone(), item.row.clone(), item.column_span.clone(), item.row_span.clone(), item.width.clone(), item.height.clone(), item.style.clone())
+        .notify(move || inner.relayout());
+
+        self.inner.items.borrow_mut().push(item);
+        self.inner.relayout();
+
+        LayoutSlot::new(self.inner.layout.relative(move |layout| layout.get(index).copied().unwrap_or_default()))
+    }
+
+    /// The bounds of every item, in the order they were added.
+    pub fn bounds(&self) -> &Signal<Vec<Bounds>> {
+        &self.inner.layout
+    }
+}
+
+impl Debug for GridLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GridLayout")
+        .field("columns", &*self.inner.grid.columns.get())
+        .field("rows", &*self.inner.grid.rows.get())
+        .field("items", &self.inner.items.borrow().len())
+        .finish_non_exhaustive()
+    }
+}
+
+impl GridLayoutInner {
+    fn relayout(&self) {
+        let layout = self.compute();
+
+        if *self.layout.get() != layout {
+            self.layout.set(layout);
+        }
+    }
+
+    fn compute(&self) -> Vec<Bounds> {
+        let grid = &self.grid;
+        let (column_gap, row_gap) = (*grid.column_gap.get() as f32, *grid.row_gap.get() as f32);
+        let (justify, align) = (*grid.justify_items.get(), *grid.align_items.get());
+
+        let area = grid.padding.get().inset(Bounds::new(*grid.x.get(), *grid.y.get(), *grid.width.get(), *grid.height.get()));
+
+        let items = self.items.borrow().iter().map(|item| {
+            let style = *item.style.get();
+            let (width, height) = style.outer_size(*item.width.get(), *item.height.get());
+
+            Placement {
+                column: *item.column.get(),
+                row: *item.row.get(),
+                column_span: (*item.column_span.get()).max(1),
+                row_span: (*item.row_span.get()).max(1),
+                width: width as f32,
+                height: height as f32,
+                style
+            }
+        }).collect::<Vec<_>>();
+
+        let columns = size_tracks(
+            &grid.columns.get(),
+            items.iter().map(|item| (item.column, item.column_span, item.width)),
+            area.width as f32,
+            column_gap
+        );
+        let rows = size_tracks(
+            &grid.rows.get(),
+            items.iter().map(|item| (item.row, item.row_span, item.height)),
+            area.height as f32,
+            row_gap
+        );
+
+        items.iter().map(|item| {
+            let (cell_x, cell_width) = span(&columns, item.column, item.column_span, column_gap);
+            let (cell_y, cell_height) = span(&rows, item.row, item.row_span, row_gap);
+
+            let (x, width) = justify.place(item.width, cell_width);
+            let (y, height) = align.place(item.height, cell_height);
+
+            item.style.from_outer(Bounds::new(
+                area.x + (cell_x + x).round() as i32,
+                area.y + (cell_y + y).round() as i32,
+                width.round().max(0.0) as u32,
+                height.round().max(0.0) as u32
+            ))
+        }).collect()
+    }
+}
+
+/// The size of every track, including the auto tracks added for items placed past the defined ones.
+/// `items` are the (first track, span, size) of every item along this axis.
+fn size_tracks(defined: &[Track], items: impl Iterator<Item = (usize, usize, f32)> + Clone, available: f32, gap: f32) -> Vec<f32> {
+    let count = items.clone().map(|(start, span, _)| start + span).max().unwrap_or(0).max(defined.len());
+    let tracks = (0..count).map(|index| defined.get(index).copied().unwrap_or(Track::Auto)).collect::<Vec<_>>();
+
+    // The largest item in each track, only counting items within a single track
+    let mut content = vec![0.0f32; count];
+    for (start, span, size) in items {
+        if span == 1 {
+            content[start] = content[start].max(size);
+        }
+    }
+
+    let mut sizes = tracks.iter().zip(&content).map(|(track, content)| match *track {
+        Track::Fixed(size) => size as f32,
+        Track::Fraction(_) => 0.0,
+        Track::Auto => *content,
+        Track::MinMax(min, max) => content.clamp(min as f32, (max as f32).max(min as f32))
+    }).collect::<Vec<_>>();
+
+    let used = |sizes: &[f32]| sizes.iter().sum::<f32>() + gap * count.saturating_sub(1) as f32;
+
+    // Min/max tracks grow towards their max first, sharing the free space evenly
+    loop {
+        let free = available - used(&sizes);
+        let growing = tracks.iter().zip(&sizes)
+        .filter(|(track, size)| matches!(track, Track::MinMax(_, max) if **size < *max as f32))
+        .count();
+
+        if free <= 0.5 || growing == 0 {
+            break;
+        }
+
+        let share = free / growing as f32;
+        for (track, size) in tracks.iter().zip(sizes.iter_mut()) {
+            if let Track::MinMax(_, max) = *track {
+                *size = (*size + share).min(max as f32).max(*size);
+            }
+        }
+    }
+
+    let free = (available - used(&sizes)).max(0.0);
+    let fractions = tracks.iter().map(|track| match track {
+        Track::Fraction(fraction) => fraction.max(0.0),
+        _ => 0.0
+    }).sum::<f32>();
+
+    if fractions > 0.0 {
+        for (track, size) in tracks.iter().zip(sizes.iter_mut()) {
+            if let Track::Fraction(fraction) = track {
+                *size = free * fraction.max(0.0) / fractions;
+            }
+        }
+    }
+
+    sizes
+}
+
+/// The offset and size of `span` tracks starting at `start`, including the gaps between them.
+fn span(sizes: &[f32], start: usize, span: usize, gap: f32) -> (f32, f32) {
+    let offset = sizes[..start].iter().map(|size| size + gap).sum::<f32>();
+    let size = sizes[start..start + span].iter().sum::<f32>() + gap * (span - 1) as f32;
+
+    (offset, size)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::{geometry::Bounds, signals::{Signal, SignalTrait}};
+
+    use super::{size_tracks, GridBuilder, GridItem, Track};
+
+    #[test]
+    fn sizes_fixed_auto_and_fractional_tracks() {
+        let tracks = [Track::Fixed(50), Track::Auto, Track::Fraction(1.0), Track::Fraction(3.0)];
+        let items = [(1, 1, 30.0), (1, 1, 20.0), (0, 2, 500.0)];
+
+        // Items spanning several tracks don't size auto tracks
+        assert_eq!(size_tracks(&tracks, items.into_iter(), 270.0, 10.0), vec![50.0, 30.0, 40.0, 120.0]);
+        // Fractional tracks don't go below 0 if there is no space left
+        assert_eq!(size_tracks(&tracks, items.into_iter(), 50.0, 10.0), vec![50.0, 30.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn grows_min_max_tracks_before_fractions() {
+        let tracks = [Track::MinMax(20, 60), Track::MinMax(10, 200), Track::Fraction(1.0)];
+        let items = [(0, 1, 30.0)];
+
+        assert_eq!(size_tracks(&tracks, items.into_iter(), 150.0, 0.0), vec![60.0, 90.0, 0.0]);
+        assert_eq!(size_tracks(&tracks, items.into_iter(), 300.0, 0.0), vec![60.0, 200.0, 40.0]);
+    }
+
+    #[test]
+    fn adds_auto_tracks_for_items_past_the_defined_ones() {
+        assert_eq!(size_tracks(&[Track::Fixed(10)], [(2, 1, 15.0)].into_iter(), 100.0, 5.0), vec![10.0, 0.0, 15.0]);
+    }
+
+    #[test]
+    fn places_items_in_their_cells() {
+        let grid = GridBuilder {
+            width: Signal::new(110),
+            height: Signal::new(100),
+            columns: Signal::new(vec![Track::Fraction(1.0), Track::Fraction(1.0)]),
+            rows: Signal::new(vec![Track::Fixed(40)]),
+            column_gap: Signal::new(10),
+            ..Default::default()
+        };
+        let layout = grid.build();
+        layout.item(GridItem::new(1, 0));
+        layout.item(GridItem::new(0, 1).with_span(2, 1).with_size(Signal::new(0), Signal::new(25)));
+
+        assert_eq!(*layout.bounds().get(), vec![Bounds::new(60, 0, 50, 40), Bounds::new(0, 40, 110, 25)]);
+
+        grid.width.set(210);
+        assert_eq!(layout.bounds().get()[0], Bounds::new(110, 0, 100, 40));
+    }
+}
@@ -0,0 +1,31 @@
+use crate::{geometry::Bounds, signals::{Signal, SignalTrait}};
+
+pub mod anchor;
+pub mod flex;
+pub mod grid;
+pub mod style;
+#[cfg(feature = "constraints")]
+pub mod constraint;
+
+
+/// The geometry a layout computed for one of its children, to be used as a widget's `x`, `y`, `width` and `height`.
+#[derive(Debug, Clone)]
+pub struct LayoutSlot {
+    pub x: Signal<i32>,
+    pub y: Signal<i32>,
+    pub width: Signal<u32>,
+    pub height: Signal<u32>,
+    pub bounds: Signal<Bounds>
+}
+
+impl LayoutSlot {
+    pub fn new(bounds: Signal<Bounds>) -> Self {
+        Self {
+            x: bounds.relative(|b| b.x),
+            y: bounds.relative(|b| b.y),
+            width: bounds.relative(|b| b.width),
+            height: bounds.relative(|b| b.height),
+            bounds
+        }
+    }
+}
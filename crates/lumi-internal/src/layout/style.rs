@@ -0,0 +1,203 @@
+use crate::geometry::Bounds;
+
+
+/// Spacing on each side of a box, in pixels.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Edges {
+    pub left: u32,
+    pub right: u32,
+    pub top: u32,
+    pub bottom: u32
+}
+
+/// The box model shared by all widgets, and honored by layout containers for their items.
+///
+/// A widget's `width` and `height` are the size of its border box, and its `x` and `y` (or the position an anchor or layout gives it)
+/// are the top left corner of its margin. Backgrounds like rectangles fill the border box,
+/// while text, images and svgs are drawn inside of the content box, within the border and padding.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct LayoutStyle {
+    pub margin: Edges,
+    /// Space reserved for a border between the margin and padding, which no widget draws a border into.
+    /// Rectangles fill it like the rest of their border box, so draw a border as a rectangle of its color,
+    /// with the background anchored inside of it.
+    pub border: Edges,
+    pub padding: Edges,
+    pub min_width: Option<u32>,
+    pub max_width: Option<u32>,
+    pub min_height: Option<u32>,
+    pub max_height: Option<u32>,
+    /// Width divided by height. Derives the height from the width once the width's min and max are applied, before the height's.
+    pub aspect_ratio: Option<f32>
+}
+
+
+impl Edges {
+    pub const fn uniform(size: u32) -> Self {
+        Self { left: size, right: size, top: size, bottom: size }
+    }
+
+    pub const fn symmetric(horizontal: u32, vertical: u32) -> Self {
+        Self { left: horizontal, right: horizontal, top: vertical, bottom: vertical }
+    }
+
+    pub fn horizontal(&self) -> u32 {
+        self.left + self.right
+    }
+
+    pub fn vertical(&self) -> u32 {
+        self.top + self.bottom
+    }
+
+    /// Shrinks `bounds` by these edges.
+    pub fn inset(&self, bounds: Bounds) -> Bounds {
+        Bounds::new(
+            bounds.x.saturating_add(self.left as i32),
+            bounds.y.saturating_add(self.top as i32),
+            bounds.width.saturating_sub(self.horizontal()),
+            bounds.height.saturating_sub(self.vertical())
+        )
+    }
+
+    fn add(self, other: Edges) -> Edges {
+        Edges {
+            left: self.left + other.left,
+            right: self.right + other.right,
+            top: self.top + other.top,
+            bottom: self.bottom + other.bottom
+        }
+    }
+}
+
+impl LayoutStyle {
+    pub fn with_margin(self, margin: Edges) -> Self {
+        Self { margin, ..self }
+    }
+
+    pub fn with_border(self, border: Edges) -> Self {
+        Self { border, ..self }
+    }
+
+    pub fn with_padding(self, padding: Edges) -> Self {
+        Self { padding, ..self }
+    }
+
+    pub fn with_min_size(self, width: u32, height: u32) -> Self {
+        Self { min_width: Some(width), min_height: Some(height), ..self }
+    }
+
+    pub fn with_max_size(self, width: u32, height: u32) -> Self {
+        Self { max_width: Some(width), max_height: Some(height), ..self }
+    }
+
+    pub fn with_aspect_ratio(self, aspect_ratio: f32) -> Self {
+        Self { aspect_ratio: Some(aspect_ratio), ..self }
+    }
+
+    /// Applies the aspect ratio and min/max sizes to a border box size.
+    /// Constraining an already constrained size doesn't change it.
+    pub fn constrain(&self, width: u32, height: u32) -> (u32, u32) {
+        // The minimum wins over the maximum, like in CSS
+        let clamp = |size: u32, min: Option<u32>, max: Option<u32>| {
+            let size = max.map_or(size, |max| size.min(max));
+            min.map_or(size, |min| size.max(min))
+        };
+
+        let width = clamp(width, self.min_width, self.max_width);
+        let height = match self.aspect_ratio {
+            Some(ratio) if ratio > 0.0 => (width as f32 / ratio).round() as u32,
+            _ => height
+        };
+
+        (width, clamp(height, self.min_height, self.max_height))
+    }
+
+    /// The border box of a widget whose geometry is `outer`: positioned at the corner of its margin, and sized like its border box.
+    pub fn place(&self, outer: Bounds) -> Bounds {
+        let (width, height) = self.constrain(outer.width, outer.height);
+
+        Bounds::new(
+            outer.x.saturating_add(self.margin.left as i32),
+            outer.y.saturating_add(self.margin.top as i32),
+            width,
+            height
+        )
+    }
+
+    /// The space an item with this border box size takes up in a layout, including its margin.
+    pub fn outer_size(&self, width: u32, height: u32) -> (u32, u32) {
+        let (width, height) = self.constrain(width, height);
+
+        (width + self.margin.horizontal(), height + self.margin.vertical())
+    }
+
+    /// The geometry for an item that was given `outer` by a layout, including its margin, which [`LayoutStyle::place`] turns back into its border box.
+    pub fn from_outer(&self, outer: Bounds) -> Bounds {
+        let (width, height) = self.constrain(
+            outer.width.saturating_sub(self.margin.horizontal()),
+            outer.height.saturating_sub(self.margin.vertical())
+        );
+
+        Bounds::new(outer.x, outer.y, width, height)
+    }
+
+    /// The area inside of the border and padding of `border_box`.
+    pub fn content(&self, border_box: Bounds) -> Bounds {
+        self.border.add(self.padding).inset(border_box)
+    }
+
+    /// The space the border and padding take up, horizontally and vertically.
+    pub fn insets(&self) -> (u32, u32) {
+        let edges = self.border.add(self.padding);
+        (edges.horizontal(), edges.vertical())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::Bounds;
+
+    use super::{Edges, LayoutStyle};
+
+    #[test]
+    fn constrains_width_before_deriving_height() {
+        let style = LayoutStyle::default().with_aspect_ratio(2.0).with_max_size(100, 40).with_min_size(20, 15);
+
+        assert_eq!(style.constrain(300, 0), (100, 40));
+        assert_eq!(style.constrain(10, 0), (20, 15));
+        assert_eq!(style.constrain(60, 999), (60, 30));
+    }
+
+    #[test]
+    fn constrain_is_idempotent() {
+        let styles = [
+            LayoutStyle::default(),
+            LayoutStyle::default().with_aspect_ratio(1.5).with_max_size(90, 50),
+            LayoutStyle::default().with_aspect_ratio(0.5).with_min_size(30, 70),
+            // Conflicting minimum and maximum
+            LayoutStyle::default().with_min_size(50, 50).with_max_size(40, 40)
+        ];
+
+        for style in styles {
+            for (width, height) in [(0, 0), (25, 80), (60, 10), (500, 500)] {
+                let constrained = style.constrain(width, height);
+                assert_eq!(style.constrain(constrained.0, constrained.1), constrained, "{style:?} with {width}x{height}");
+            }
+        }
+    }
+
+    #[test]
+    fn outer_size_and_from_outer_round_trip() {
+        let style = LayoutStyle::default().with_margin(Edges { left: 4, right: 6, top: 1, bottom: 3 }).with_max_size(50, 50);
+
+        assert_eq!(style.outer_size(80, 20), (60, 24));
+
+        let inner = style.from_outer(Bounds::new(10, 20, 60, 24));
+        assert_eq!(inner, Bounds::new(10, 20, 50, 20));
+        assert_eq!(style.place(inner), Bounds::new(14, 21, 50, 20));
+
+        // Margins larger than the given space leave nothing for the border box
+        assert_eq!(style.from_outer(Bounds::new(0, 0, 5, 2)), Bounds::new(0, 0, 0, 0));
+    }
+}
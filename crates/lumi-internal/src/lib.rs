@@ -1,4 +1,6 @@
-use std::sync::{LazyLock, OnceLock};
+use std::{cell::{Cell, RefCell}, collections::HashMap, sync::{LazyLock, Mutex, OnceLock}};
+#[cfg(feature = "hot_reload")]
+use std::any::Any;
 
 use crossbeam_channel::Sender;
 use custom_event::CustomEvent;
@@ -6,9 +8,13 @@ use futures::executor::ThreadPool;
 use log::{error, info};
 use lumi2d::types::Event;
 
-use crate::frame_notifier::FrameNotifier;
+use crate::{animations::clock::AnimationClock, frame_notifier::FrameNotifier, elements::{lifecycle::Cleanups, query::LabelIndex, suspense::Suspense}, signals::Signal, timer::Timer};
+#[cfg(feature = "hot_reload")]
+use crate::elements::window::WindowInner;
 
 pub use lumi2d;
+#[doc(hidden)]
+pub use lumi_macros::view as __view;
 
 pub mod macros;
 
@@ -21,11 +27,20 @@ pub mod byte_source;
 pub mod callback;
 pub mod animations;
 pub mod frame_notifier;
+pub mod geometry;
+pub mod layout;
+pub mod timer;
+#[cfg(feature = "hot_reload")]
+pub mod hot_reload;
+#[cfg(feature = "loader")]
+pub mod loader;
 
 
 pub static LOADING_COLOR: u32 = 0x57595C66;
 
 pub(crate) static GLOBAL_SENDER: OnceLock<Sender<Event<CustomEvent>>> = OnceLock::new();
+/// Events sent before the backend was initialized, like timers that were due early
+static PENDING_EVENTS: Mutex<Vec<Event<CustomEvent>>> = Mutex::new(Vec::new());
 
 #[cfg(feature = "reqwest")]
 pub(crate) static REQWEST_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(reqwest::Client::new);
@@ -33,7 +48,25 @@ pub(crate) static REQWEST_CLIENT: LazyLock<reqwest::Client> = LazyLock::new(reqw
 pub(crate) static UREQ_CLIENT: LazyLock<ureq::Agent> = LazyLock::new(ureq::Agent::new);
 
 thread_local! {
-    pub static LOCAL_FRAME_NOTIFIER: FrameNotifier = FrameNotifier::new();
+    #[deprecated(note = "Listeners are run on the wall clock for now, add them to an `AnimationClock` instead")]
+    pub static LOCAL_FRAME_NOTIFIER: FrameNotifier = const { FrameNotifier::new() };
+    pub(crate) static LOCAL_ANIMATION_CLOCK: RefCell<AnimationClock> = RefCell::new(AnimationClock::system());
+    pub(crate) static LOCAL_ACTIVE_CLOCKS: RefCell<Vec<AnimationClock>> = const { RefCell::new(Vec::new()) };
+    pub(crate) static LOCAL_REDUCED_MOTION: Signal<bool> = Signal::new(false);
+    pub(crate) static LOCAL_TIMERS: RefCell<HashMap<u64, Timer>> = RefCell::new(HashMap::new());
+    pub(crate) static LOCAL_CLEANUPS: RefCell<HashMap<u64, Cleanups>> = RefCell::new(HashMap::new());
+    pub(crate) static LOCAL_CLEANUP_SCOPES: RefCell<Vec<Cleanups>> = const { RefCell::new(Vec::new()) };
+    pub(crate) static LOCAL_LABELS: RefCell<LabelIndex> = RefCell::new(LabelIndex::default());
+    /// The depth and identifier of the innermost scroll view under the cursor, while a scroll event is handled
+    pub(crate) static LOCAL_SCROLL_TARGET: Cell<Option<(usize, u64)>> = const { Cell::new(None) };
+    pub(crate) static LOCAL_SUSPENSE_SCOPES: RefCell<Vec<Suspense>> = const { RefCell::new(Vec::new()) };
+    #[cfg(feature = "hot_reload")]
+    pub(crate) static LOCAL_HOT_RELOADING: Cell<bool> = const { Cell::new(false) };
+    /// The signals of `hot_signal`, with the size and alignment of their values
+    #[cfg(feature = "hot_reload")]
+    pub(crate) static LOCAL_HOT_SIGNALS: RefCell<HashMap<String, ((usize, usize), Box<dyn Any>)>> = RefCell::new(HashMap::new());
+    #[cfg(feature = "hot_reload")]
+    pub(crate) static LOCAL_RETAINED_WINDOWS: RefCell<Vec<WindowInner>> = const { RefCell::new(Vec::new()) };
 }
 
 pub static THREAD_POOL: LazyLock<ThreadPool> = LazyLock::new(|| {
@@ -51,5 +84,27 @@ pub(crate) fn global_send(event: Event<CustomEvent>) {
     if let Some(sender) = GLOBAL_SENDER.get() {
         sender.send(event)
         .map_err(|err| error!("Failed to global send an event: {}", err)).ok();
+        return;
+    }
+
+    let mut pending = PENDING_EVENTS.lock().unwrap();
+
+    // Checked again while locked, as the backend may have been initialized meanwhile
+    match GLOBAL_SENDER.get() {
+        Some(sender) => {
+            sender.send(event)
+            .map_err(|err| error!("Failed to global send an event: {}", err)).ok();
+        },
+        None => pending.push(event)
+    }
+}
+
+/// Sets the sender of the backend, and sends the events that were queued before it existed.
+pub(crate) fn set_global_sender(sender: Sender<Event<CustomEvent>>) {
+    let mut pending = PENDING_EVENTS.lock().unwrap();
+    GLOBAL_SENDER.set(sender).unwrap();
+
+    for event in pending.drain(..) {
+        global_send(event);
     }
 }
\ No newline at end of file
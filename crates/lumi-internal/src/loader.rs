@@ -0,0 +1,478 @@
+use std::{any::{type_name, Any}, collections::HashMap, fmt::Display, fs, io, marker::PhantomData, path::{Path, PathBuf}};
+
+use lumi2d::types::{Rounding, TextOptions};
+use serde::{de::{Deserializer, Error, IntoDeserializer, Visitor}, Deserialize};
+
+use crate::{byte_source::ByteSource, callback::Callback, elements::{element_builder::{ElementBuilder, ElementBuilderTrait}, window::{WindowBuilder, WindowState}}, signals::{Signal, SignalTrait}, widgets::{image::ImageBuilder, interact::InteractBuilder, rectangle::RectangleBuilder, svg::SvgBuilder, text::TextBuilder, widget_builder::WidgetBuilder}};
+
+
+/// Builds element trees from RON files, so layouts can be changed without recompiling.
+///
+/// A file contains a list of nodes, like `[Window(state: "main", children: [Rectangle(x: 10, width: "$width")])]`.
+/// Every property is either a value, or `"$name"` to use the signal bound to that name with [`UiLoader::bind`].
+/// Text starting with a `$` is written with `$$` instead.
+/// Outputs like `hovered` and callbacks like `clicked` only take the name of a binding, without the `$`.
+#[derive(Debug, Default)]
+pub struct UiLoader {
+    bindings: HashMap<String, Binding>,
+    base_path: PathBuf
+}
+
+struct Binding(Box<dyn Any>);
+
+#[derive(Debug)]
+pub enum UiError {
+    Io(io::Error),
+    Parse(ron::error::SpannedError),
+    /// A node refers to a binding that was never bound.
+    MissingBinding { node: String, field: &'static str, binding: String },
+    /// A binding holds another type than the field it is used for.
+    WrongType { node: String, field: &'static str, binding: String, expected: &'static str },
+    /// Every widget other than a window needs to be inside of a window.
+    OutsideWindow { node: String }
+}
+
+/// A property value, or the name of a binding to take it from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Prop<T> {
+    Bind(String),
+    Value(T)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+enum Node {
+    Window {
+        /// A binding to the `WindowState` of the window
+        state: Option<String>,
+        #[serde(default)]
+        children: Vec<Node>
+    },
+    Rectangle {
+        #[serde(default)]
+        x: Prop<i32>,
+        #[serde(default)]
+        y: Prop<i32>,
+        #[serde(default)]
+        width: Prop<u32>,
+        #[serde(default)]
+        height: Prop<u32>,
+        #[serde(default)]
+        color: Prop<u32>,
+        rounding: Option<u32>,
+        #[serde(default)]
+        children: Vec<Node>
+    },
+    Text {
+        #[serde(default)]
+        x: Prop<i32>,
+        #[serde(default)]
+        y: Prop<i32>,
+        #[serde(default)]
+        width: Prop<u32>,
+        max_height: Option<Prop<u32>>,
+        #[serde(default)]
+        text: Prop<String>,
+        size: Option<f32>,
+        #[serde(default)]
+        italic: bool,
+        #[serde(default)]
+        underline: bool,
+        /// A binding to the `Signal<TextOptions>` to use instead of size, italic and underline
+        options: Option<String>
+    },
+    Image {
+        #[serde(default)]
+        x: Prop<i32>,
+        #[serde(default)]
+        y: Prop<i32>,
+        #[serde(default)]
+        width: Prop<u32>,
+        #[serde(default)]
+        height: Prop<u32>,
+        /// A path relative to the base path, or a binding to a `Signal<ByteSource>`
+        source: Prop<String>,
+        #[serde(default)]
+        children: Vec<Node>
+    },
+    Svg {
+        #[serde(default)]
+        x: Prop<i32>,
+        #[serde(default)]
+        y: Prop<i32>,
+        #[serde(default)]
+        width: Prop<u32>,
+        #[serde(default)]
+        height: Prop<u32>,
+        source: Prop<String>,
+        #[serde(default)]
+        color: Prop<u32>,
+        #[serde(default)]
+        children: Vec<Node>
+    },
+    Interact {
+        #[serde(default)]
+        x: Prop<i32>,
+        #[serde(default)]
+        y: Prop<i32>,
+        #[serde(default)]
+        width: Prop<u32>,
+        #[serde(default)]
+        height: Prop<u32>,
+        hovered: Option<String>,
+        click_left: Option<String>,
+        click_right: Option<String>,
+        click_middle: Option<String>,
+        clicked: Option<String>,
+        right_clicked: Option<String>,
+        #[serde(default)]
+        children: Vec<Node>
+    }
+}
+
+
+impl UiLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes `value` available to nodes as `"$name"`. Usually a `Signal`, but can be a `Callback` or `WindowState` as well.
+    pub fn bind<T: Clone + 'static>(mut self, name: impl Into<String>, value: T) -> Self {
+        self.bindings.insert(name.into(), Binding(Box::new(value)));
+        self
+    }
+
+    /// The path image and svg sources are relative to, defaults to the working directory.
+    pub fn with_base_path(self, path: impl Into<PathBuf>) -> Self {
+        Self {
+            base_path: path.into(),
+            ..self
+        }
+    }
+
+    /// Parses `source`, and returns a root builder containing its nodes.
+    pub fn load(&self, source: &str) -> Result<ElementBuilder, UiError> {
+        let root = ElementBuilder::root();
+        self.load_into(&root, source)?;
+
+        Ok(root)
+    }
+
+    /// Reads the file at `path`, with sources relative to its directory if no base path was set.
+    pub fn load_file(&self, path: impl AsRef<Path>) -> Result<ElementBuilder, UiError> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(UiError::Io)?;
+
+        let base_path = if self.base_path.as_os_str().is_empty() {
+            path.parent().unwrap_or(Path::new(""))
+        } else {
+            &self.base_path
+        };
+
+        let root = ElementBuilder::root();
+        self.add_nodes(&root, &source, base_path)?;
+
+        Ok(root)
+    }
+
+    /// Parses `source` and adds its nodes to `parent`, which is assumed to be inside of a window unless it is a root.
+    /// Nothing is added if any node is invalid.
+    pub fn load_into(&self, parent: &ElementBuilder, source: &str) -> Result<(), UiError> {
+        self.add_nodes(parent, source, &self.base_path)
+    }
+
+    fn add_nodes(&self, parent: &ElementBuilder, source: &str, base_path: &Path) -> Result<(), UiError> {
+        let nodes: Vec<Node> = ron::from_str(source).map_err(UiError::Parse)?;
+        let in_window = !matches!(parent, ElementBuilder::Root(_));
+
+        let staging = ElementBuilder::root();
+        for (index, node) in nodes.iter().enumerate() {
+            self.add_node(&staging, node, &path_of(None, node, index), in_window, base_path)?;
+        }
+
+        parent.children().write().unwrap().extend(staging.children().read().unwrap().iter().cloned());
+
+        Ok(())
+    }
+
+    fn add_node(&self, parent: &ElementBuilder, node: &Node, path: &str, in_window: bool, base_path: &Path) -> Result<(), UiError> {
+        if !in_window && !matches!(node, Node::Window { .. }) {
+            return Err(UiError::OutsideWindow { node: path.to_string() });
+        }
+
+        let (widget, children) = match node {
+            Node::Window { state, children } => {
+                let state = match state {
+                    Some(name) => self.binding::<WindowState>(name, path, "state")?,
+                    None => WindowState::default()
+                };
+
+                (WidgetBuilder::Window(WindowBuilder { state, ..Default::default() }), children)
+            },
+            Node::Rectangle { x, y, width, height, color, rounding, children } => (
+                RectangleBuilder {
+                    x: self.prop(x, path, "x")?,
+                    y: self.prop(y, path, "y")?,
+                    width: self.prop(width, path, "width")?,
+                    height: self.prop(height, path, "height")?,
+                    color: self.prop(color, path, "color")?,
+                    rounding: Signal::constant(rounding.map(|r| Rounding::new_uniform(r as _))),
+                    ..Default::default()
+                }.into(),
+                children
+            ),
+            Node::Text { x, y, width, max_height, text, size, italic, underline, options } => {
+                let options = match options {
+                    Some(name) => self.binding::<Signal<TextOptions>>(name, path, "options")?,
+                    None => {
+                        let defaults = TextOptions::default();
+                        Signal::constant(TextOptions {
+                            size: size.unwrap_or(defaults.size),
+                            italic: *italic,
+                            underline: *underline,
+                            ..defaults
+                        })
+                    }
+                };
+                let max_height = match max_height {
+                    Some(max_height) => self.prop(max_height, path, "max_height")?.relative(|h| Some(*h)),
+                    None => Signal::constant(None)
+                };
+
+                let text = TextBuilder {
+                    x: self.prop(x, path, "x")?,
+                    y: self.prop(y, path, "y")?,
+                    width: self.prop(width, path, "width")?,
+                    text: self.prop(text, path, "text")?,
+                    max_height,
+                    options,
+                    ..Default::default()
+                };
+
+                // Text can't have children
+                parent.child_widget(text.into());
+                return Ok(());
+            },
+            Node::Image { x, y, width, height, source, children } => (
+                ImageBuilder {
+                    x: self.prop(x, path, "x")?,
+                    y: self.prop(y, path, "y")?,
+                    width: self.prop(width, path, "width")?,
+                    height: self.prop(height, path, "height")?,
+                    source: self.source(source, path, base_path)?,
+                    ..Default::default()
+                }.into(),
+                children
+            ),
+            Node::Svg { x, y, width, height, source, color, children } => (
+                SvgBuilder {
+                    x: self.prop(x, path, "x")?,
+                    y: self.prop(y, path, "y")?,
+                    width: self.prop(width, path, "width")?,
+                    height: self.prop(height, path, "height")?,
+                    color: self.prop(color, path, "color")?,
+                    source: self.source(source, path, base_path)?,
+                    ..Default::default()
+                }.into(),
+                children
+            ),
+            Node::Interact { x, y, width, height, hovered, click_left, click_right, click_middle, clicked, right_clicked, children } => {
+                let output = |name: &Option<String>, field| match name {
+                    Some(name) => self.binding::<Signal<bool>>(name, path, field),
+                    None => Ok(Signal::new(false))
+                };
+                let callback = |name: &Option<String>, field| name.as_ref()
+                .map(|name| self.binding::<Callback>(name, path, field))
+                .transpose();
+
+                (
+                    InteractBuilder {
+                        x: self.prop(x, path, "x")?,
+                        y: self.prop(y, path, "y")?,
+                        width: self.prop(width, path, "width")?,
+                        height: self.prop(height, path, "height")?,
+                        hovered: output(hovered, "hovered")?,
+                        click_left: output(click_left, "click_left")?,
+                        click_right: output(click_right, "click_right")?,
+                        click_middle: output(click_middle, "click_middle")?,
+                        clicked: callback(clicked, "clicked")?,
+                        right_clicked: callback(right_clicked, "right_clicked")?,
+                        ..Default::default()
+                    }.into(),
+                    children
+                )
+            }
+        };
+
+        let element = parent.child_widget(widget);
+
+        for (index, child) in children.iter().enumerate() {
+            self.add_node(&element, child, &path_of(Some(path), child, index), true, base_path)?;
+        }
+
+        Ok(())
+    }
+
+    fn prop<T: Clone + 'static>(&self, prop: &Prop<T>, node: &str, field: &'static str) -> Result<Signal<T>, UiError> {
+        match prop {
+            Prop::Value(value) => Ok(Signal::constant(value.clone())),
+            Prop::Bind(name) => self.binding::<Signal<T>>(name, node, field)
+        }
+    }
+
+    fn source(&self, source: &Prop<String>, node: &str, base_path: &Path) -> Result<Signal<ByteSource>, UiError> {
+        match source {
+            Prop::Value(path) => Ok(Signal::constant(ByteSource::from_path(base_path.join(path)))),
+            Prop::Bind(name) => self.binding::<Signal<ByteSource>>(name, node, "source")
+        }
+    }
+
+    fn binding<T: Clone + 'static>(&self, name: &str, node: &str, field: &'static str) -> Result<T, UiError> {
+        let binding = self.bindings.get(name).ok_or_else(|| UiError::MissingBinding {
+            node: node.to_string(),
+            field,
+            binding: name.to_string()
+        })?;
+
+        binding.0.downcast_ref::<T>().cloned().ok_or_else(|| UiError::WrongType {
+            node: node.to_string(),
+            field,
+            binding: name.to_string(),
+            expected: type_name::<T>()
+        })
+    }
+}
+
+/// Where a node is in the file, like `Window[0] > Rectangle[2]`.
+fn path_of(parent: Option<&str>, node: &Node, index: usize) -> String {
+    let kind = match node {
+        Node::Window { .. } => "Window",
+        Node::Rectangle { .. } => "Rectangle",
+        Node::Text { .. } => "Text",
+        Node::Image { .. } => "Image",
+        Node::Svg { .. } => "Svg",
+        Node::Interact { .. } => "Interact"
+    };
+
+    match parent {
+        Some(parent) => format!("{parent} > {kind}[{index}]"),
+        None => format!("{kind}[{index}]")
+    }
+}
+
+impl<T: Default> Default for Prop<T> {
+    fn default() -> Self {
+        Prop::Value(T::default())
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Prop<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // Read as any value, as strings may be bindings to values of any type
+        deserializer.deserialize_any(PropVisitor(PhantomData))
+    }
+}
+
+/// Hands everything but bindings to `T` right away, so errors name the type it expected.
+struct PropVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for PropVisitor<T> {
+    type Value = Prop<T>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "a {} or a \"$binding\"", type_name::<T>())
+    }
+
+    fn visit_bool<E: Error>(self, value: bool) -> Result<Self::Value, E> {
+        T::deserialize(value.into_deserializer()).map(Prop::Value)
+    }
+
+    fn visit_i64<E: Error>(self, value: i64) -> Result<Self::Value, E> {
+        T::deserialize(value.into_deserializer()).map(Prop::Value)
+    }
+
+    fn visit_u64<E: Error>(self, value: u64) -> Result<Self::Value, E> {
+        T::deserialize(value.into_deserializer()).map(Prop::Value)
+    }
+
+    fn visit_f64<E: Error>(self, value: f64) -> Result<Self::Value, E> {
+        T::deserialize(value.into_deserializer()).map(Prop::Value)
+    }
+
+    fn visit_str<E: Error>(self, text: &str) -> Result<Self::Value, E> {
+        if let Some(name) = text.strip_prefix('$') && !name.starts_with('$') {
+            return Ok(Prop::Bind(name.to_string()));
+        }
+
+        // `$$` stands for a literal `$`
+        let text = text.strip_prefix('$').unwrap_or(text);
+        T::deserialize(text.to_string().into_deserializer()).map(Prop::Value)
+    }
+}
+
+impl Display for UiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UiError::Io(err) => write!(f, "Failed to read the file: {err}"),
+            UiError::Parse(err) => write!(f, "Failed to parse: {err}"),
+            UiError::MissingBinding { node, field, binding } => write!(f, "{node}: {field} is bound to {binding}, which doesn't exist"),
+            UiError::WrongType { node, field, binding, expected } => write!(f, "{node}: {field} is bound to {binding}, which isn't a {expected}"),
+            UiError::OutsideWindow { node } => write!(f, "{node} needs to be inside of a window")
+        }
+    }
+}
+
+impl std::fmt::Debug for Binding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Binding")
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use crate::signals::Signal;
+
+    use super::{Node, Prop, UiError, UiLoader};
+
+    const SOURCE: &str = r#"[Window(children: [
+        Rectangle(x: 10, width: "$width", color: 0xFF0000FF),
+        Text(text: "$$5", max_height: Some("$height"))
+    ])]"#;
+
+    #[test]
+    fn parses_bindings_and_values() {
+        let nodes: Vec<Node> = ron::from_str(SOURCE).unwrap();
+        let Node::Window { children, .. } = &nodes[0] else { panic!("Expected a window, got {nodes:?}") };
+
+        let Node::Rectangle { x, width, color, .. } = &children[0] else { panic!("Expected a rectangle, got {children:?}") };
+        assert_eq!((x, width, color), (&Prop::Value(10), &Prop::Bind("width".to_string()), &Prop::Value(0xFF0000FF)));
+
+        let Node::Text { text, max_height, .. } = &children[1] else { panic!("Expected a text, got {children:?}") };
+        assert_eq!((text, max_height), (&Prop::Value("$5".to_string()), &Some(Prop::Bind("height".to_string()))));
+    }
+
+    #[test]
+    fn names_the_expected_type() {
+        let error = |source: &str| ron::from_str::<Vec<Node>>(source).unwrap_err().to_string();
+
+        assert!(error("[Rectangle(x: 1.5)]").contains("Expected i32"));
+        assert!(error("[Rectangle(width: -3)]").contains("Expected u32"));
+        assert!(error("[Rectangle(widht: 3)]").contains("Unexpected field named `widht`"));
+    }
+
+    #[test]
+    fn loads_file_with_bindings() {
+        let path = env::temp_dir().join(format!("lumi-loader-{}.ron", process::id()));
+        fs::write(&path, SOURCE).unwrap();
+
+        let missing = UiLoader::new().bind("width", Signal::new(120u32)).load_file(&path);
+        let loaded = UiLoader::new().bind("width", Signal::new(120u32)).bind("height", Signal::new(40u32)).load_file(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(missing, Err(UiError::MissingBinding { field: "max_height", ref binding, .. }) if binding == "height"), "{missing:?}");
+        assert!(loaded.is_ok(), "{loaded:?}");
+    }
+}
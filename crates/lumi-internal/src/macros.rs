@@ -1,3 +1,5 @@
+use crate::signals::Signal;
+
 
 #[macro_export]
 macro_rules! use_signals {
@@ -17,4 +19,72 @@ macro_rules! use_signals {
             $sig.notify(test.clone());
         })*
     }};
-}
\ No newline at end of file
+}
+
+/// Builds an element tree from a nested, declarative description.
+///
+/// ```ignore
+/// let root = view! {
+///     Window {
+///         Rectangle(x: 10, y: 10, width: 200, height: 100, color: 0xFF0000FF) {
+///             Text("hi", x: 20, y: 20, width: 180)
+///         }
+///     }
+/// };
+/// ```
+///
+/// - `Window`, `Rectangle`, `Text`, `Image`, `Svg` and `Interact` build the widget of the same name.
+///   Unset fields keep their default, `Text`, `Image` and `Svg` take their text / source as an optional first argument.
+/// - Any other name is a `Component`, built from a struct literal of the given fields.
+/// - Plain values are wrapped into constant signals, signals are used as they are.
+/// - `z_index` is set on the widget's element.
+/// - `{ expr }` adds an already existing component, `ElementBuilder` or `WidgetBuilder`.
+///   Directly after a widget, separate it with a comma so it isn't taken for the widget's children.
+/// - `if signal { ... } else { ... }` rebuilds its children whenever the `Signal<bool>` changes,
+///   `for pattern in signal { ... }` whenever the signal's collection changes.
+///   Both move what they use into the dynamic element, so clone signals used afterwards.
+///
+/// Without a parent, `view!` creates and returns a new root. `view! { parent => ... }` adds the children to `parent` instead.
+#[macro_export]
+macro_rules! view {
+    ($($tokens:tt)*) => {
+        $crate::__view!($crate; $($tokens)*)
+    };
+}
+
+/// Converts the values given to `view!` into the type of the field they are assigned to.
+/// Plain values are wrapped into constant signals (and `Some`, if the field is optional),
+/// values which already have the field's type are passed through unchanged.
+pub trait IntoProp<T> {
+    fn into_prop(self) -> T;
+}
+
+impl<T> IntoProp<T> for T {
+    fn into_prop(self) -> T {
+        self
+    }
+}
+
+impl<T: 'static> IntoProp<Signal<T>> for T {
+    fn into_prop(self) -> Signal<T> {
+        Signal::constant(self)
+    }
+}
+
+impl<T: 'static> IntoProp<Signal<Option<T>>> for T {
+    fn into_prop(self) -> Signal<Option<T>> {
+        Signal::constant(Some(self))
+    }
+}
+
+impl<T> IntoProp<Option<T>> for T {
+    fn into_prop(self) -> Option<T> {
+        Some(self)
+    }
+}
+
+impl IntoProp<Signal<String>> for &str {
+    fn into_prop(self) -> Signal<String> {
+        Signal::constant(self.to_string())
+    }
+}
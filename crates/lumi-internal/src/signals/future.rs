@@ -2,7 +2,7 @@ use std::{future::Future, marker::PhantomData, sync::RwLock};
 
 use lumi2d::types::Event;
 
-use crate::custom_event::CustomEvent;
+use crate::{custom_event::CustomEvent, elements::suspense::Suspense};
 
 use super::*;
 
@@ -11,6 +11,9 @@ pub struct FutureSignal<T, U> {
     pub(crate) data: Arc<RwLock<FutureState<T>>>,
     pub(crate) slots: Arc<RwLock<Vec<Slot<FutureState<T>>>>>,
     pub(crate) notif_slots: Arc<RwLock<Vec<NotifSlot>>>,
+    /// Run when a new future is set, so suspenses show their fallback again.
+    /// Subscribers are only invoked once it completes.
+    pub(crate) restart_slots: Arc<RwLock<Vec<NotifSlot>>>,
     pub(crate) _phantom: PhantomData<U>
 }
 
@@ -28,6 +31,7 @@ impl<T: Send + Sync + 'static, U: Future<Output = T> + Send + 'static> FutureSig
             data: Arc::new(RwLock::new(FutureState::Running)),
             slots: Arc::new(RwLock::new(Vec::new())),
             notif_slots: Arc::new(RwLock::new(Vec::new())),
+            restart_slots: Arc::new(RwLock::new(Vec::new())),
             _phantom: PhantomData
         }
     }
@@ -36,6 +40,10 @@ impl<T: Send + Sync + 'static, U: Future<Output = T> + Send + 'static> FutureSig
         *self.data.write().unwrap() = FutureState::Running;
     }
 
+    pub(crate) fn on_restart(&self, callback: impl Fn() + 'static) {
+        self.restart_slots.write().unwrap().push(NotifSlot::new(callback));
+    }
+
     fn invoke(&self) {
         let current = self.data.read().unwrap();
     
@@ -51,11 +59,16 @@ impl<T: Send + Sync + 'static, U: Future<Output = T> + Send + 'static> FutureSig
 
 impl<U: Send + Sync + 'static, T: Future<Output = U> + Send + 'static> SignalTrait<'_, T, FutureState<U>> for FutureSignal<U, T> {
     fn get(&self) -> SignalRef<FutureState<U>> {
+        Suspense::read(self);
+
         SignalRef::RwLock(self.data.read().unwrap())
     }
 
     fn set(&self, data: T) {
         self.set_running_state();
+        for restart_slot in self.restart_slots.read().unwrap().iter() {
+            restart_slot.invoke()
+        }
 
         let raw = Box::into_raw(Box::new(self.clone()));
         let pointer = raw as usize;
@@ -159,6 +172,7 @@ impl<T, U> Clone for FutureSignal<T, U> {
             data: self.data.clone(),
             slots: self.slots.clone(),
             notif_slots: self.notif_slots.clone(),
+            restart_slots: self.restart_slots.clone(),
             _phantom: PhantomData
         }
     }
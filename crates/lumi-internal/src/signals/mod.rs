@@ -1,4 +1,4 @@
-use std::{cell::{Cell, Ref}, fmt::{Debug, Display}, ops::Deref, rc::Rc, sync::{Arc, RwLockReadGuard}, time::{Duration, Instant}};
+use std::{cell::{Cell, Ref}, fmt::{Debug, Display}, ops::Deref, rc::Rc, sync::{Arc, RwLockReadGuard}, time::Duration};
 
 mod combined;
 mod root;
@@ -10,7 +10,7 @@ mod slots;
 use r#const::ConstSignal;
 use num_traits::AsPrimitive;
 
-use crate::{animations::easings::EasingFunction, frame_notifier::FrameListener};
+use crate::{animations::{clock::AnimationClock, easings::EasingFunction, motion::Motion}, frame_notifier::FrameListener};
 
 pub use {slots::*, root::*, future::*};
 
@@ -72,18 +72,34 @@ impl<T: 'static> Signal<T> {
 
 impl<T: Clone + 'static + AsPrimitive<f32>> Signal<T> where f32: AsPrimitive<T> {
     pub fn animate(&self, duration: Duration, easing: EasingFunction) -> Self {
+        self.animate_with(&AnimationClock::local(), duration, easing, Motion::Reducible)
+    }
+
+    /// Like `animate`, but keeps animating while reduced motion is enabled.
+    pub fn animate_essential(&self, duration: Duration, easing: EasingFunction) -> Self {
+        self.animate_with(&AnimationClock::local(), duration, easing, Motion::Essential)
+    }
+
+    pub fn animate_with(&self, clock: &AnimationClock, duration: Duration, easing: EasingFunction, motion: Motion) -> Self {
         let _clone = self.clone();
         let previous = Cell::new(self.get().cloned());
         let new_signal = Signal::new(self.get().cloned());
         let cloned_new = new_signal.clone();
         let easing = Rc::new(easing);
+        let clock = clock.clone();
 
         self.subscribe(move |new| {
             let start = previous.replace(new.clone());
             let end = new.clone();
             let clone = cloned_new.clone();
+
+            if !motion.allowed() {
+                clone.set(end);
+                return;
+            }
+
             let easing = easing.clone();
-            let start_time = Instant::now();
+            let start_time = clock.now();
 
             dbg!(start.as_(), end.as_());
 
@@ -92,11 +108,9 @@ impl<T: Clone + 'static + AsPrimitive<f32>> Signal<T> where f32: AsPrimitive<T>
                 // log::debug!("Current progress: {:?}", progress);
 
                 clone.set(easing.calculate(&start, &end, progress));
-            }, Instant::now() + duration);
+            }, start_time + duration);
 
-            crate::LOCAL_FRAME_NOTIFIER.with(|notifier| {
-                notifier.add(listener);
-            });
+            clock.add(listener);
         });
 
         // self.relative(|input| easing.calculate(input, progress));
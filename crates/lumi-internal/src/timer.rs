@@ -0,0 +1,241 @@
+use std::{cell::{Cell, RefCell}, cmp::Reverse, collections::{BinaryHeap, HashMap}, fmt::Debug, rc::Rc, sync::{Condvar, LazyLock, Mutex}, thread, time::{Duration, Instant}};
+
+use log::{error, info};
+use lumi2d::types::Event;
+
+use crate::{animations::clock::AnimationClock, custom_event::CustomEvent, elements::window::{Window, WindowRef}};
+
+
+/// Returned by frame callbacks to decide whether they should run again next frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameAction {
+    Continue,
+    Stop
+}
+
+/// The shortest interval of `set_interval`, so a zero interval doesn't keep the event loop busy.
+pub const MIN_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Runs `callback` on the UI thread once `delay` has passed, and then redraws every window.
+/// Timers that are due before the backend is initialized run once it is.
+pub fn set_timeout(delay: Duration, callback: impl FnOnce() + 'static) -> TimerHandle {
+    TimerHandle::schedule(delay, TimerCallback::Timeout(Box::new(callback)))
+}
+
+/// Runs `callback` on the UI thread every `interval`, until the returned handle is cancelled.
+/// The interval is at least `MIN_INTERVAL`.
+///
+/// Every window is redrawn after each run, as it isn't known which ones the callback changed.
+/// Prefer `request_frame` for work that only affects one window every frame.
+pub fn set_interval(interval: Duration, callback: impl Fn() + 'static) -> TimerHandle {
+    let interval = interval.max(MIN_INTERVAL);
+    TimerHandle::schedule(interval, TimerCallback::Interval(interval, Rc::new(callback)))
+}
+
+/// Runs `callback` before every frame of `window` is drawn, until it returns `FrameAction::Stop`.
+/// The callback receives the time of the animation clock.
+pub fn request_frame(window: &Window, callback: impl FnMut(Instant) -> FrameAction + 'static) -> FrameHandle {
+    let cancelled = Rc::new(Cell::new(false));
+
+    window.inner.frame_callbacks.add(FrameCallback {
+        callback: Box::new(callback),
+        cancelled: cancelled.clone()
+    });
+    crate::global_send(Event::Custom(CustomEvent::Redraw(window.id())));
+
+    FrameHandle { cancelled, window: window.weak() }
+}
+
+
+#[derive(Debug)]
+pub struct TimerHandle {
+    id: u64
+}
+
+impl TimerHandle {
+    fn schedule(delay: Duration, callback: TimerCallback) -> Self {
+        let id = fastrand::u64(..);
+        let deadline = Instant::now() + delay;
+
+        crate::LOCAL_TIMERS.with(|timers| {
+            timers.borrow_mut().insert(id, Timer { callback, deadline });
+        });
+        WAKER.wake_at(deadline, id);
+
+        Self { id }
+    }
+
+    pub fn cancel(&self) {
+        crate::LOCAL_TIMERS.with(|timers| timers.borrow_mut().remove(&self.id));
+    }
+
+    /// Returns true until the timeout ran or the timer was cancelled.
+    pub fn is_active(&self) -> bool {
+        crate::LOCAL_TIMERS.with(|timers| timers.borrow().contains_key(&self.id))
+    }
+}
+
+#[derive(Debug)]
+pub struct FrameHandle {
+    cancelled: Rc<Cell<bool>>,
+    window: WindowRef
+}
+
+impl FrameHandle {
+    pub fn cancel(&self) {
+        self.cancelled.set(true);
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.cancelled.get() && self.window.strong_count() > 0
+    }
+}
+
+
+pub(crate) struct Timer {
+    callback: TimerCallback,
+    deadline: Instant
+}
+
+enum TimerCallback {
+    Timeout(Box<dyn FnOnce()>),
+    Interval(Duration, Rc<dyn Fn()>)
+}
+
+/// Runs the timer with the given id, called when its `CustomEvent::Timer` arrives.
+pub(crate) fn fire(id: u64) {
+    let Some(timer) = crate::LOCAL_TIMERS.with(|timers| timers.borrow_mut().remove(&id)) else {
+        return; // Cancelled
+    };
+
+    match timer.callback {
+        TimerCallback::Timeout(callback) => callback(),
+        TimerCallback::Interval(interval, callback) => {
+            // Scheduled from the previous deadline so intervals don't drift, unless we fell behind
+            let deadline = (timer.deadline + interval).max(Instant::now());
+
+            // Reinserted before running, so the callback can cancel its own interval
+            crate::LOCAL_TIMERS.with(|timers| {
+                timers.borrow_mut().insert(id, Timer {
+                    callback: TimerCallback::Interval(interval, callback.clone()),
+                    deadline
+                });
+            });
+            WAKER.wake_at(deadline, id);
+
+            callback.as_ref()();
+        }
+    }
+}
+
+impl Debug for Timer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Timer")
+        .field("deadline", &self.deadline)
+        .finish_non_exhaustive()
+    }
+}
+
+
+/// The frame callbacks of one window.
+#[derive(Default)]
+pub(crate) struct FrameCallbacks {
+    callbacks: RefCell<Vec<FrameCallback>>
+}
+
+struct FrameCallback {
+    callback: Box<dyn FnMut(Instant) -> FrameAction>,
+    cancelled: Rc<Cell<bool>>
+}
+
+impl FrameCallbacks {
+    fn add(&self, callback: FrameCallback) {
+        self.callbacks.borrow_mut().push(callback);
+    }
+
+    /// Returns true if there are callbacks left for the next frame.
+    pub(crate) fn run(&self) -> bool {
+        // Taken out, so callbacks can request new frames while running
+        let mut running = std::mem::take(&mut *self.callbacks.borrow_mut());
+        let time = AnimationClock::local().now();
+
+        running.retain_mut(|frame| {
+            if frame.cancelled.get() {
+                return false;
+            }
+
+            let keep = (frame.callback)(time) == FrameAction::Continue;
+            frame.cancelled.set(!keep);
+
+            keep
+        });
+
+        let mut callbacks = self.callbacks.borrow_mut();
+        running.append(&mut callbacks);
+        *callbacks = running;
+
+        !callbacks.is_empty()
+    }
+}
+
+impl Debug for FrameCallbacks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FrameCallbacks")
+        .field("Callback count", &self.callbacks.borrow().len())
+        .finish()
+    }
+}
+
+
+static WAKER: LazyLock<Waker> = LazyLock::new(Waker::spawn);
+
+/// Sleeps on its own thread until the next timer is due, and then wakes the event loop.
+struct Waker {
+    deadlines: Mutex<BinaryHeap<Reverse<(Instant, u64)>>>,
+    condvar: Condvar
+}
+
+impl Waker {
+    fn spawn() -> Self {
+        let waker = Self {
+            deadlines: Mutex::new(BinaryHeap::new()),
+            condvar: Condvar::new()
+        };
+
+        thread::Builder::new()
+        .name("lumi-timer".to_string())
+        .spawn(|| WAKER.run())
+        .map_err(|err| error!("Failed to spawn the timer thread: {err}"))
+        .ok();
+
+        info!("Timer thread created.");
+
+        waker
+    }
+
+    fn wake_at(&self, deadline: Instant, id: u64) {
+        self.deadlines.lock().unwrap().push(Reverse((deadline, id)));
+        self.condvar.notify_one();
+    }
+
+    fn run(&self) {
+        let mut deadlines = self.deadlines.lock().unwrap();
+
+        loop {
+            let now = Instant::now();
+            let next = deadlines.peek().map(|Reverse(next)| *next);
+
+            deadlines = match next {
+                None => self.condvar.wait(deadlines).unwrap(),
+                Some((deadline, _)) if deadline > now => {
+                    self.condvar.wait_timeout(deadlines, deadline - now).unwrap().0
+                },
+                Some((_, id)) => {
+                    deadlines.pop();
+                    crate::global_send(Event::Custom(CustomEvent::Timer(id)));
+                    deadlines
+                }
+            };
+        }
+    }
+}
@@ -1,18 +1,24 @@
+use log::error;
 use lumi2d::types::{CacheableImage, Event, Object};
 
-use crate::{backend::Backend, byte_source::ByteSource, custom_event::CustomEvent, elements::window::Window, signals::{FutureSignal, FutureState, Signal, SignalRef, SignalTrait}};
+use crate::{animations::layout::LayoutAnimation, backend::Backend, byte_source::ByteSource, custom_event::CustomEvent, elements::error_boundary::BoundaryError, geometry::Bounds, layout::{anchor::Anchors, style::LayoutStyle}, signals::{FutureSignal, FutureState, Signal, SignalRef, SignalTrait}};
 
-use super::{widget_builder::WidgetBuilderTrait, Widget, WidgetTrait};
+use super::{content_bounds, layout_bounds, raw_bounds, widget_builder::{BuildContext, WidgetBuilderTrait}, Widget, WidgetTrait};
 
 #[derive(Debug)]
 pub struct Image {
-    pub object: Signal<Object>
+    pub object: Signal<Object>,
+    pub bounds: Signal<Bounds>
 }
 
 impl WidgetTrait for Image {
     fn get_objects(&self) -> Option<SignalRef<Object>> {
         Some(self.object.get())
     }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Some(*self.bounds.get())
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -21,46 +27,77 @@ pub struct ImageBuilder {
     pub y: Signal<i32>,
     pub width: Signal<u32>,
     pub height: Signal<u32>,
-    pub source: Signal<ByteSource>
+    pub source: Signal<ByteSource>,
+    pub style: Signal<LayoutStyle>,
+    pub anchors: Option<Signal<Anchors>>,
+    pub animate_layout: Option<LayoutAnimation>
 }
 
 impl WidgetBuilderTrait for ImageBuilder {
-    fn build(&self, _backend: &Backend, window: Option<&Window>) -> Widget {
+    fn build(&self, _backend: &Backend, context: &BuildContext) -> Widget {
         let decoder = FutureSignal::empty();
 
         let clone = decoder.clone();
         self.source.relative(move |source| {
             let source = source.clone();
             clone.set(async move {
-                let bytes = source.get().await.unwrap();
+                let bytes = source.get().await.map_err(|err| format!("{err:?}"))?;
                 let image = CacheableImage::from_encoded(&bytes);
 
-                image
+                Ok::<_, String>(image)
             });
         });
 
-        let window_id = window.map(|w| w.id());
-        decoder.subscribe(move |state| if let FutureState::Completed(_) = state {
+        let window_id = context.window.as_ref().map(|w| w.id());
+        let boundary = context.boundary.clone();
+        decoder.subscribe(move |state| if let FutureState::Completed(result) = state {
+            if let Err(err) = result {
+                match &boundary {
+                    Some(boundary) => boundary.report(BoundaryError::Load(err.clone())),
+                    None => error!("Failed to load an image: {err}")
+                }
+            }
+
             if let Some(win) = window_id.clone() {
                 crate::global_send(Event::Custom(CustomEvent::Redraw(win)));
             }
         });
 
-        let combined = (self.x.clone(), self.y.clone(), self.width.clone(), self.height.clone(), decoder.relative(|state| state.clone()));
+        let bounds = layout_bounds(raw_bounds(&self.x, &self.y, &self.width, &self.height), &self.style, &self.anchors, context, &self.animate_layout);
+        let combined = (content_bounds(&bounds, &self.style), decoder.relative(|state| state.clone()), context.transform.clone(), context.clip.clone());
 
-        let object = combined.relative(move |(x,y, w, h, image)| {
-            let (x, y, w, h) = (**x, **y, **w, **h);
+        let object = combined.relative(move |(bounds, image, transform, clip)| {
+            let transformed = transform.apply_bounds(**bounds);
+            let placed = transform.place_bounds(**bounds);
+            let Bounds { x, y, width: w, height: h } = placed;
 
-            match image.as_ref() {
+            if clip.as_ref().as_ref().is_some_and(|clip| !clip.intersects(transformed)) {
+                return Object::rectangle(x, y, 0, 0, 0, None);
+            }
+
+            let object = match image.as_ref() {
                 FutureState::Running => {
-                    Object::rectangle(x, y, w, h, crate::LOADING_COLOR, None)
+                    Object::rectangle(x, y, w, h, transform.apply_color(crate::LOADING_COLOR), None)
+                },
+                // Images can't be drawn translucent, so they are only hidden once fully transparent
+                FutureState::Completed(Ok(_)) if transform.is_invisible() => {
+                    Object::rectangle(x, y, w, h, 0, None)
                 },
-                FutureState::Completed(image) => {
+                FutureState::Completed(Err(_)) => {
+                    Object::rectangle(x, y, 0, 0, 0, None)
+                },
+                FutureState::Completed(Ok(image)) => {
                     Object::image(x, y, w, h, image.clone())
                 }
+            };
+            let object = transform.rotate_object(placed, object);
+
+            match clip.as_ref() {
+                Some(clip) => clip.apply(transformed, object),
+                None => object
             }
         });
 
-        Widget::Image(Image { object })
+        Widget::Image(Image { object, bounds })
     }
 }
\ No newline at end of file
@@ -1,19 +1,44 @@
-use std::ops::Deref;
+use std::{ops::Deref, sync::RwLock};
 
 use lumi2d::types::{Object, Position};
 
-use crate::{backend::Backend, callback::Callback, elements::window::Window, signals::{Signal, SignalRef, SignalTrait, Slot}};
+use crate::{animations::layout::LayoutAnimation, backend::Backend, callback::Callback, elements::{element::Element, window::WindowRef}, geometry::{Bounds, Clip, Transform}, layout::{anchor::Anchors, style::LayoutStyle}, signals::{Signal, SignalRef, SignalTrait, Slot}};
 
-use super::{widget_builder::WidgetBuilderTrait, Widget, WidgetTrait};
+use super::{layout_bounds, raw_bounds, widget_builder::{BuildContext, WidgetBuilderTrait}, Widget, WidgetTrait};
 
 #[derive(Debug)]
 pub struct Interact {
+    pub bounds: Signal<Bounds>,
+    /// The transform of the groups above it, which is inverted for hit tests
+    pub transform: Signal<Transform>,
+    /// The clip of the groups above it, outside of which it can't be hit
+    pub clip: Signal<Option<Clip>>,
+    pub(crate) hit_id: u64
+}
+
+/// The `Interact` widgets of a window in the order they were last drawn, to find the one on top.
+/// Only they block input, the bounds other widgets are drawn with aren't tracked.
+#[derive(Debug, Default)]
+pub(crate) struct HitTargets {
+    targets: RwLock<Vec<HitTarget>>
+}
+
+#[derive(Debug)]
+struct HitTarget {
+    hit_id: u64,
+    bounds: Signal<Bounds>,
+    transform: Signal<Transform>,
+    clip: Signal<Option<Clip>>
 }
 
 impl WidgetTrait for Interact {
     fn get_objects(&self) -> Option<SignalRef<Object>> {
         None
     }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Some(*self.bounds.get())
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -28,24 +53,30 @@ pub struct InteractBuilder {
     pub click_middle: Signal<bool>,
     pub clicked: Option<Callback>,
     pub right_clicked: Option<Callback>,
-    pub mouse_drag: Option<Slot<Position<f64>>>
+    pub mouse_drag: Option<Slot<Position<f64>>>,
+    pub style: Signal<LayoutStyle>,
+    pub anchors: Option<Signal<Anchors>>,
+    pub animate_layout: Option<LayoutAnimation>
 }
 
 impl WidgetBuilderTrait for InteractBuilder {
-    fn build(&self, _backend: &Backend, window: Option<&Window>) -> Widget {
+    fn build(&self, _backend: &Backend, context: &BuildContext) -> Widget {
         let cloned = self.clone();
-        let state = &window.unwrap().innerest().state;
+        let window = context.window.as_ref().unwrap();
+        let state = &window.innerest().state;
+        let (weak_window, hit_id) = (window.weak(), fastrand::u64(..));
+        let (clip, transform) = (context.clip.clone(), context.transform.clone());
         let hovered = self.hovered.clone();
         let click_left = self.click_left.clone();
         let mouse_drag = self.mouse_drag.clone();
         let cursor_pos = state.cursor_pos.clone();
-        let combined = (cloned.x, cloned.y, cloned.width, cloned.height);
+        let bounds = layout_bounds(raw_bounds(&self.x, &self.y, &self.width, &self.height), &self.style, &self.anchors, context, &self.animate_layout);
+        let (hit_bounds, hit_transform, hit_clip) = (bounds.clone(), transform.clone(), clip.clone());
         
         state.cursor_pos.subscribe(move |pos| {
             // TODO: Optimize this somehow? I feel like this is going to be slow
-            let (x, y, w, h) = combined.get().cloned();
-            
-            let is_within = pos_within(*x, *y, *w, *h, pos);
+            let is_within = hit_test(*hit_bounds.get(), &hit_transform.get(), &hit_clip.get(), pos)
+            && is_on_top(&weak_window, hit_id, pos);
 
             if *hovered.get() {
                 if !is_within {
@@ -60,19 +91,20 @@ impl WidgetBuilderTrait for InteractBuilder {
 
             if let Some(cb) = &cloned.mouse_drag {
                 if *click_left.get() {
-                    cb.invoke(pos);
+                    cb.invoke(&to_local(&transform.get(), pos));
                 }
             }
         });
 
         let hovered = self.hovered.clone();
+        let drag_transform = context.transform.clone();
         state.click_left.subscribe(move |down| {
             let hover = *hovered.get();
             if *down {
                 if hover {
                     cloned.click_left.set(true);
                     if let Some(cb) = &mouse_drag {
-                        cb.invoke(cursor_pos.get().deref());
+                        cb.invoke(&to_local(&drag_transform.get(), cursor_pos.get().deref()));
                     }
                 }
             } else if *cloned.click_left.get() {
@@ -115,8 +147,66 @@ impl WidgetBuilderTrait for InteractBuilder {
         });
         
 
-        Widget::Interact(Interact {  })
+        Widget::Interact(Interact { bounds, transform: context.transform.clone(), clip, hit_id })
+    }
+}
+
+impl HitTargets {
+    pub(crate) fn update(&self, drawn: &[Element]) {
+        let targets = drawn.iter()
+        .filter_map(|element| match element {
+            Element::Widget(widget_element) => match widget_element.widget() {
+                Widget::Interact(interact) => Some(HitTarget {
+                    hit_id: interact.hit_id,
+                    bounds: interact.bounds.clone(),
+                    transform: interact.transform.clone(),
+                    clip: interact.clip.clone()
+                }),
+                _ => None
+            },
+            _ => None
+        })
+        .collect();
+
+        *self.targets.write().unwrap() = targets;
+    }
+
+    fn contains(&self, hit_id: u64) -> bool {
+        self.targets.read().unwrap().iter().any(|target| target.hit_id == hit_id)
     }
+
+    /// The id of the topmost target containing `pos`.
+    fn topmost(&self, pos: &Position<f64>) -> Option<u64> {
+        self.targets.read().unwrap().iter().rev()
+        .find(|target| hit_test(*target.bounds.get(), &target.transform.get(), &target.clip.get(), pos))
+        .map(|target| target.hit_id)
+    }
+}
+
+/// Widgets which weren't drawn yet count as being on top, so they work before the first frame.
+fn is_on_top(window: &WindowRef, hit_id: u64, pos: &Position<f64>) -> bool {
+    let Some(window) = window.upgrade() else { return false };
+    let topmost = window.hit_targets.topmost(pos);
+
+    topmost.is_none_or(|id| id == hit_id || !window.hit_targets.contains(hit_id))
+}
+
+/// Maps a cursor position from window space into the space the widget was laid out in.
+fn to_local(transform: &Transform, pos: &Position<f64>) -> Position<f64> {
+    match transform.invert_point(pos.x as f32, pos.y as f32) {
+        Some((x, y)) => Position::new(x as f64, y as f64),
+        None => Position::new(pos.x, pos.y)
+    }
+}
+
+/// Widgets in groups that are invisible can't be hit, and neither can the parts of them that are clipped.
+fn hit_test(bounds: Bounds, transform: &Transform, clip: &Option<Clip>, pos: &Position<f64>) -> bool {
+    let Bounds { x, y, width, height } = bounds;
+
+    !transform.is_invisible()
+    && clip.as_ref().is_none_or(|clip| clip.contains(pos.x as f32, pos.y as f32))
+    && transform.invert_point(pos.x as f32, pos.y as f32)
+    .is_some_and(|(tx, ty)| pos_within(x, y, width, height, &Position::new(tx as f64, ty as f64)))
 }
 
 #[inline]
@@ -2,7 +2,7 @@ use enum_dispatch::enum_dispatch;
 
 use lumi2d::types::Object;
 
-use crate::signals::SignalRef;
+use crate::{animations::layout::LayoutAnimation, geometry::Bounds, layout::{anchor::Anchors, style::LayoutStyle}, signals::{Signal, SignalRef, SignalTrait}, widgets::widget_builder::BuildContext};
 
 pub mod widget_builder;
 pub mod rectangle;
@@ -26,4 +26,54 @@ pub enum Widget {
 pub trait WidgetTrait {
     fn expected_children(&self) -> usize { 1 }
     fn get_objects(&self) -> Option<SignalRef<Object>>;
-}
\ No newline at end of file
+    /// The untransformed area this widget occupies.
+    fn bounds(&self) -> Option<Bounds>;
+}
+
+impl Widget {
+    /// The area this widget occupies, which anchored children are positioned in.
+    pub fn bounds_signal(&self) -> &Signal<Bounds> {
+        match self {
+            Widget::Rectangle(rectangle) => &rectangle.bounds,
+            Widget::Text(text) => &text.bounds,
+            Widget::Image(image) => &image.bounds,
+            Widget::Svg(svg) => &svg.bounds,
+            Widget::Interact(interact) => &interact.bounds
+        }
+    }
+}
+
+
+/// The bounds of a widget's border box from its builder's geometry, resolved against its parent if it is anchored,
+/// and animated if it opted into layout animations.
+pub(crate) fn layout_bounds(
+    raw: Signal<Bounds>,
+    style: &Signal<LayoutStyle>,
+    anchors: &Option<Signal<Anchors>>,
+    context: &BuildContext,
+    animation: &Option<LayoutAnimation>
+) -> Signal<Bounds> {
+    let bounds = match anchors {
+        // The margin keeps anchored widgets away from the parent's edges
+        Some(anchors) => (anchors.clone(), context.parent_bounds(), raw, style.clone()).relative(|(anchors, parent, own, style)| {
+            anchors.resolve(style.margin.inset(**parent), **own, style)
+        }),
+        None => (raw, style.clone()).relative(|(own, style)| style.place(**own))
+    };
+
+    match animation {
+        Some(animation) => animation.animate(&bounds),
+        None => bounds
+    }
+}
+
+pub(crate) fn raw_bounds(x: &Signal<i32>, y: &Signal<i32>, width: &Signal<u32>, height: &Signal<u32>) -> Signal<Bounds> {
+    (x.clone(), y.clone(), width.clone(), height.clone()).relative(|(x, y, w, h)| {
+        Bounds::new(**x, **y, **w, **h)
+    })
+}
+
+/// Where the content of a widget with the border box `bounds` is drawn.
+pub(crate) fn content_bounds(bounds: &Signal<Bounds>, style: &Signal<LayoutStyle>) -> Signal<Bounds> {
+    (bounds.clone(), style.clone()).relative(|(bounds, style)| style.content(**bounds))
+}
@@ -1,18 +1,24 @@
 use lumi2d::types::{Rounding, Object};
 
-use crate::{backend::Backend, elements::window::Window, signals::{Signal, SignalRef, SignalTrait}};
+use crate::{animations::layout::LayoutAnimation, backend::Backend, geometry::Bounds, layout::{anchor::Anchors, style::LayoutStyle}, signals::{Signal, SignalRef, SignalTrait}};
 
-use super::{widget_builder::WidgetBuilderTrait, Widget, WidgetTrait};
+use super::{layout_bounds, raw_bounds, widget_builder::{BuildContext, WidgetBuilderTrait}, Widget, WidgetTrait};
 
 #[derive(Debug)]
 pub struct Rectangle {
-    pub rectangle: Signal<Object>
+    pub rectangle: Signal<Object>,
+    pub bounds: Signal<Bounds>,
+    pub color: Signal<u32>
 }
 
 impl WidgetTrait for Rectangle {
     fn get_objects(&self) -> Option<SignalRef<Object>> {
         Some(self.rectangle.get())
     }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Some(*self.bounds.get())
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -22,17 +28,43 @@ pub struct RectangleBuilder {
     pub width: Signal<u32>,
     pub height: Signal<u32>,
     pub color: Signal<u32>,
-    pub rounding: Signal<Option<Rounding>>
+    pub rounding: Signal<Option<Rounding>>,
+    pub style: Signal<LayoutStyle>,
+    pub anchors: Option<Signal<Anchors>>,
+    pub animate_layout: Option<LayoutAnimation>
 }
 
 impl WidgetBuilderTrait for RectangleBuilder {
-    fn build(&self, _backend: &Backend, _window: Option<&Window>) -> Widget {
-        let combined = (self.x.clone(), self.y.clone(), self.width.clone(), self.height.clone(), self.color.clone(), self.rounding.clone());
+    fn build(&self, _backend: &Backend, context: &BuildContext) -> Widget {
+        let bounds = layout_bounds(raw_bounds(&self.x, &self.y, &self.width, &self.height), &self.style, &self.anchors, context, &self.animate_layout);
+        let combined = (bounds.clone(), self.color.clone(), self.rounding.clone(), context.transform.clone(), context.clip.clone());
+
+        let rectangle = combined.relative(|(bounds, c, r, transform, clip)| {
+            let b = transform.apply_bounds(**bounds);
+            let color = transform.apply_color(**c);
+
+            // Rotated rectangles can't be cut down, so the renderer clips them
+            if transform.is_rotated() {
+                let placed = transform.place_bounds(**bounds);
+                let object = transform.rotate_object(placed, Object::rectangle(placed.x, placed.y, placed.width, placed.height, color, r.cloned()));
 
-        let rectangle = combined.relative(|(x,y, w, h, c, r)| {
-            Object::rectangle(**x, **y, **w, **h, **c, r.cloned())
+                return match clip.as_ref() {
+                    Some(clip) => clip.apply(b, object),
+                    None => object
+                };
+            }
+
+            let Some(clip) = clip.as_ref() else {
+                return Object::rectangle(b.x, b.y, b.width, b.height, color, r.cloned());
+            };
+
+            match clip.cut(b) {
+                Some(cut) if cut == b => Object::rectangle(b.x, b.y, b.width, b.height, color, r.cloned()),
+                Some(cut) => Object::rectangle(cut.x, cut.y, cut.width, cut.height, color, clip.corner_rounding(b, cut, r.as_ref().as_ref())),
+                None => Object::rectangle(b.x, b.y, 0, 0, 0, None)
+            }
         });
 
-        Widget::Rectangle(Rectangle { rectangle })
+        Widget::Rectangle(Rectangle { rectangle, bounds, color: self.color.clone() })
     }
-}
\ No newline at end of file
+}
@@ -1,18 +1,25 @@
+use log::error;
 use lumi2d::types::{CacheableSvg, Event, Object};
 
-use crate::{backend::Backend, byte_source::ByteSource, custom_event::CustomEvent, elements::window::Window, signals::{FutureSignal, FutureState, Signal, SignalRef, SignalTrait}};
+use crate::{animations::layout::LayoutAnimation, backend::Backend, byte_source::ByteSource, custom_event::CustomEvent, elements::error_boundary::BoundaryError, geometry::Bounds, layout::{anchor::Anchors, style::LayoutStyle}, signals::{FutureSignal, FutureState, Signal, SignalRef, SignalTrait}};
 
-use super::{widget_builder::WidgetBuilderTrait, Widget, WidgetTrait};
+use super::{content_bounds, layout_bounds, raw_bounds, widget_builder::{BuildContext, WidgetBuilderTrait}, Widget, WidgetTrait};
 
 #[derive(Debug)]
 pub struct Svg {
-    pub object: Signal<Object>
+    pub object: Signal<Object>,
+    pub bounds: Signal<Bounds>,
+    pub color: Signal<u32>
 }
 
 impl WidgetTrait for Svg {
     fn get_objects(&self) -> Option<SignalRef<Object>> {
         Some(self.object.get())
     }
+
+    fn bounds(&self) -> Option<Bounds> {
+        Some(*self.bounds.get())
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -22,43 +29,72 @@ pub struct SvgBuilder {
     pub width: Signal<u32>,
     pub height: Signal<u32>,
     pub color: Signal<u32>,
-    pub source: Signal<ByteSource>
+    pub source: Signal<ByteSource>,
+    pub style: Signal<LayoutStyle>,
+    pub anchors: Option<Signal<Anchors>>,
+    pub animate_layout: Option<LayoutAnimation>
 }
 
 impl WidgetBuilderTrait for SvgBuilder {
-    fn build(&self, _backend: &Backend, window: Option<&Window>) -> Widget {
+    fn build(&self, _backend: &Backend, context: &BuildContext) -> Widget {
         let source = FutureSignal::empty();
 
         let clone = source.clone();
         self.source.relative(move |byte_source| {
             let byte_source = byte_source.clone();
             clone.set(async move {
-                CacheableSvg::new(byte_source.get().await.unwrap())
+                byte_source.get().await
+                .map(CacheableSvg::new)
+                .map_err(|err| format!("{err:?}"))
             });
         });
 
-        let window_id = window.map(|w| w.id());
-        source.subscribe(move |state| if let FutureState::Completed(_) = state {
+        let window_id = context.window.as_ref().map(|w| w.id());
+        let boundary = context.boundary.clone();
+        source.subscribe(move |state| if let FutureState::Completed(result) = state {
+            if let Err(err) = result {
+                match &boundary {
+                    Some(boundary) => boundary.report(BoundaryError::Load(err.clone())),
+                    None => error!("Failed to load an svg: {err}")
+                }
+            }
+
             if let Some(win) = window_id.clone() {
                 crate::global_send(Event::Custom(CustomEvent::Redraw(win)));
             }
         });
 
-        let combined = (self.x.clone(), self.y.clone(), self.width.clone(), self.height.clone(), self.color.clone(), source.relative(|state| state.clone()));
+        let bounds = layout_bounds(raw_bounds(&self.x, &self.y, &self.width, &self.height), &self.style, &self.anchors, context, &self.animate_layout);
+        let combined = (content_bounds(&bounds, &self.style), self.color.clone(), source.relative(|state| state.clone()), context.transform.clone(), context.clip.clone());
 
-        let object = combined.relative(move |(x,y, w, h, color, source)| {
-            let (x, y, w, h, color) = (**x, **y, **w, **h, **color);
+        let object = combined.relative(move |(bounds, color, source, transform, clip)| {
+            let transformed = transform.apply_bounds(**bounds);
+            let placed = transform.place_bounds(**bounds);
+            let Bounds { x, y, width: w, height: h } = placed;
 
-            match source.as_ref() {
+            if clip.as_ref().as_ref().is_some_and(|clip| !clip.intersects(transformed)) {
+                return Object::rectangle(x, y, 0, 0, 0, None);
+            }
+
+            let object = match source.as_ref() {
                 FutureState::Running => {
-                    Object::rectangle(x, y, w, h, crate::LOADING_COLOR, None)
+                    Object::rectangle(x, y, w, h, transform.apply_color(crate::LOADING_COLOR), None)
                 },
-                FutureState::Completed(svg) => {
-                    Object::svg(x, y, w, h, svg.clone(), color)
+                FutureState::Completed(Err(_)) => {
+                    Object::rectangle(x, y, 0, 0, 0, None)
+                },
+                FutureState::Completed(Ok(svg)) => {
+                    Object::svg(x, y, w, h, svg.clone(), transform.apply_color(**color))
                 }
+            };
+            let object = transform.rotate_object(placed, object);
+
+            match clip.as_ref() {
+                Some(clip) => clip.apply(transformed, object),
+                None => object
             }
         });
 
-        Widget::Svg(Svg { object })
+        Widget::Svg(Svg { object, bounds, color: self.color.clone() })
     }
 }
\ No newline at end of file
@@ -1,13 +1,17 @@
-use lumi2d::types::{Object, TextOptions};
+use std::time::Duration;
 
-use crate::{backend::Backend, elements::window::Window, signals::{Signal, SignalRef, SignalTrait}};
+use lumi2d::types::{Event, Object, TextOptions};
 
-use super::{widget_builder::WidgetBuilderTrait, Widget, WidgetTrait};
+use crate::{animations::layout::LayoutAnimation, backend::Backend, custom_event::CustomEvent, geometry::Bounds, layout::{anchor::Anchors, style::LayoutStyle}, signals::{Signal, SignalRef, SignalTrait}, timer::set_timeout};
+
+use super::{content_bounds, layout_bounds, raw_bounds, widget_builder::{BuildContext, WidgetBuilderTrait}, Widget, WidgetTrait};
 
 
 #[derive(Debug)]
 pub struct Text {
-    pub paragraph: Signal<Object>
+    pub paragraph: Signal<Object>,
+    pub bounds: Signal<Bounds>,
+    pub text: Signal<String>
 }
 
 impl WidgetTrait for Text {
@@ -15,6 +19,10 @@ impl WidgetTrait for Text {
         Some(self.paragraph.get())
     }
 
+    fn bounds(&self) -> Option<Bounds> {
+        Some(*self.bounds.get())
+    }
+
     fn expected_children(&self) -> usize {
         0
     }
@@ -27,23 +35,87 @@ pub struct TextBuilder {
     pub text: Signal<String>,
     pub options: Signal<TextOptions>,
     pub width: Signal<u32>,
-    pub max_height: Signal<Option<u32>>
+    pub max_height: Signal<Option<u32>>,
+    /// Set to the height of the laid out paragraph, once it has been created.
+    pub measured_height: Signal<u32>,
+    /// Set to the width of the paragraph's longest line.
+    pub measured_width: Signal<u32>,
+    pub line_count: Signal<usize>,
+    pub style: Signal<LayoutStyle>,
+    pub anchors: Option<Signal<Anchors>>,
+    pub animate_layout: Option<LayoutAnimation>
+}
+
+impl TextBuilder {
+    /// The measured height plus the vertical insets of the style, for layouts to reserve for this text.
+    pub fn outer_height(&self) -> Signal<u32> {
+        (self.measured_height.clone(), self.style.clone()).relative(|(measured, style)| **measured + style.insets().1)
+    }
 }
 
 impl WidgetBuilderTrait for TextBuilder {
-    fn build(&self, backend: &Backend, _window: Option<&Window>) -> Widget {
-        let combined = (self.x.clone(), self.y.clone(), self.text.clone(), self.options.clone(), self.width.clone(), self.max_height.clone());
+    fn build(&self, backend: &Backend, context: &BuildContext) -> Widget {
+        // Until the paragraph has been measured (or if it never is, as it's clipped away), a single line is the best guess
+        let height = (self.max_height.clone(), self.options.clone(), self.measured_height.clone(), self.style.clone()).relative(|(max_h, options, measured, style)| {
+            let content = (**max_h).unwrap_or(if **measured > 0 { **measured } else { options.size.ceil() as u32 });
+            content + style.insets().1
+        });
+        let bounds = layout_bounds(raw_bounds(&self.x, &self.y, &self.width, &height), &self.style, &self.anchors, context, &self.animate_layout);
+
+        // The paragraph wraps at the width of the content box
+        let combined = (content_bounds(&bounds, &self.style), self.text.clone(), self.options.clone(), self.max_height.clone(), context.transform.clone(), context.clip.clone());
         let weak = backend.weak();
+        let measured = (self.measured_width.clone(), self.measured_height.clone(), self.line_count.clone());
+        let window_id = context.window.as_ref().map(|w| w.id());
 
-        let paragraph = combined.relative(move |(x, y, text, options, width, max_h)| {
+        let paragraph = combined.relative(move |(bounds, text, options, max_h, transform, clip)| {
             let backend = weak.upgrade().unwrap();
-            let paragraph = backend.backend.data().create_paragraph(text.cloned(), **width, max_h.cloned(), options.cloned());
 
-            Object::paragraph(**x, **y, paragraph)
+            let (x, y) = transform.apply_point(bounds.x as f32, bounds.y as f32);
+
+            if clip.as_ref().as_ref().is_some_and(|clip| !clip.intersects(transform.apply_bounds(**bounds))) {
+                return Object::rectangle(x.round() as i32, y.round() as i32, 0, 0, 0, None);
+            }
+            let mut options = options.cloned();
+            options.size *= transform.scale_y;
+            options.color = transform.apply_color(options.color);
+            let width = (bounds.width as f32 * transform.scale_x).round() as u32;
+            let max_h = (**max_h).map(|h| (h as f32 * transform.scale_y).round() as u32);
+
+            let paragraph = backend.backend.data().create_paragraph(text.cloned(), width, max_h, options);
+
+            // Measured in the untransformed space, like the rest of the widget's geometry
+            let metrics = (
+                (paragraph.longest_line() / transform.scale_x).ceil() as u32,
+                (paragraph.height() / transform.scale_y).ceil() as u32,
+                paragraph.line_count()
+            );
+            if !transform.is_invisible() && metrics != (*measured.0.get(), *measured.1.get(), *measured.2.get()) {
+                // The text's own bounds depend on its height, which are still borrowed here,
+                // so the measurement is set afterwards and laid out in another frame
+                let (measured, window_id) = (measured.clone(), window_id.clone());
+                set_timeout(Duration::ZERO, move || {
+                    measured.set(metrics);
+
+                    if let Some(win) = window_id {
+                        crate::global_send(Event::Custom(CustomEvent::Redraw(win)));
+                    }
+                });
+            }
+
+            // Text overflowing its bounds is drawn as well, so the clip has to cover the whole paragraph
+            let covered = Bounds::new(bounds.x, bounds.y, bounds.width, metrics.1);
+            let placed = transform.place_bounds(covered);
+            let object = transform.rotate_object(placed, Object::paragraph(placed.x, placed.y, paragraph));
+
+            match clip.as_ref() {
+                Some(clip) => clip.apply(transform.apply_bounds(covered), object),
+                None => object
+            }
         });
 
         Widget::Text(
-            Text { paragraph }
+            Text { paragraph, bounds, text: self.text.clone() }
         )
     }
-}
\ No newline at end of file
+}
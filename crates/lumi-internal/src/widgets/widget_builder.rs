@@ -1,6 +1,6 @@
 use enum_dispatch::enum_dispatch;
 
-use crate::{backend::Backend, elements::window::{Window, WindowBuilder}};
+use crate::{backend::Backend, elements::{element::{Element, ElementRef, ElementRefTrait}, error_boundary::ErrorBoundary, window::{Window, WindowBuilder}}, geometry::{Bounds, Clip, Transform}, signals::Signal};
 
 use super::*;
 
@@ -20,5 +20,44 @@ pub enum WidgetBuilder {
 #[enum_dispatch]
 pub trait WidgetBuilderTrait {
     fn expected_children(&self) -> usize { 1 }
-    fn build(&self, backend: &Backend, window: Option<&Window>) -> Widget;
-}
\ No newline at end of file
+    fn build(&self, backend: &Backend, context: &BuildContext) -> Widget;
+}
+
+/// Everything a widget inherits from the elements above it.
+#[derive(Debug, Clone)]
+pub struct BuildContext {
+    pub window: Option<Window>,
+    /// The combined transform of all groups between the widget and its window.
+    pub transform: Signal<Transform>,
+    /// The area the widget is clipped to, already transformed.
+    pub clip: Signal<Option<Clip>>,
+    /// The closest error boundary, which failed loads are reported to.
+    pub boundary: Option<ErrorBoundary>,
+    /// The element the widget is built under.
+    pub parent: Option<Element>
+}
+
+impl BuildContext {
+    pub(crate) fn new(parent: Option<&ElementRef>) -> Self {
+        let parent = parent.and_then(|p| p.upgrade_element());
+
+        Self {
+            window: parent.as_ref().and_then(|p| p.get_window()),
+            transform: parent.as_ref().map_or_else(
+                || Signal::constant(Transform::IDENTITY),
+                |p| p.get_transform()
+            ),
+            clip: parent.as_ref().map_or_else(
+                || Signal::constant(None),
+                |p| p.get_clip()
+            ),
+            boundary: parent.as_ref().and_then(ErrorBoundary::closest),
+            parent
+        }
+    }
+
+    /// The area anchored widgets are positioned in, see [`Element::get_anchor_bounds`].
+    pub fn parent_bounds(&self) -> Signal<Bounds> {
+        self.parent.as_ref().map_or_else(|| Signal::constant(Bounds::default()), |p| p.get_anchor_bounds())
+    }
+}
@@ -0,0 +1,364 @@
+use proc_macro2::{Spacing, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{braced, parenthesized, parse::{Parse, ParseStream}, parse_macro_input, spanned::Spanned, token, Expr, Ident, Pat, Path, Token};
+
+
+/// Implements `lumi_internal::view!`, which passes `$crate` and a `;` before the nodes,
+/// so the expansion works through `lumi_ui` as well as through `lumi_internal`.
+#[proc_macro]
+pub fn view(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let view = parse_macro_input!(input as View);
+    let krate = view.krate;
+    let mut generator = Generator { count: 0, krate: krate.clone() };
+    let parent = Ident::new("__lumi_parent", Span::mixed_site());
+    let nodes = generator.nodes(&parent, &view.nodes);
+
+    match view.parent {
+        Some(expr) => quote! {{
+            let #parent: &#krate::elements::element_builder::ElementBuilder = &(#expr);
+            #nodes
+        }},
+        None => quote! {{
+            let #parent = #krate::elements::element_builder::ElementBuilder::root();
+            #nodes
+            #parent
+        }}
+    }.into()
+}
+
+
+struct View {
+    krate: TokenStream,
+    parent: Option<Expr>,
+    nodes: Vec<Node>
+}
+
+enum Node {
+    Widget(WidgetNode),
+    Expr(Expr),
+    If(IfNode),
+    For(ForNode)
+}
+
+struct WidgetNode {
+    path: Path,
+    positional: Option<Expr>,
+    fields: Vec<(Ident, Expr)>,
+    children: Option<Vec<Node>>
+}
+
+struct IfNode {
+    condition: Expr,
+    then: Vec<Node>,
+    otherwise: Vec<Node>
+}
+
+struct ForNode {
+    pattern: Pat,
+    signal: Expr,
+    body: Vec<Node>
+}
+
+
+impl Parse for View {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let krate = input.step(|cursor| {
+            let mut rest = *cursor;
+            let mut krate = TokenStream::new();
+
+            while let Some((tree, next)) = rest.token_tree() {
+                if matches!(&tree, TokenTree::Punct(punct) if punct.as_char() == ';') {
+                    return Ok((krate, next));
+                }
+                krate.extend([tree]);
+                rest = next;
+            }
+
+            Err(cursor.error("expected the path of lumi's crate and a `;`, use `view!` instead"))
+        })?;
+
+        let parent = if has_parent(input) {
+            let expr = input.parse()?;
+            input.parse::<Token![=>]>()?;
+            Some(expr)
+        } else {
+            None
+        };
+
+        Ok(Self { krate, parent, nodes: parse_nodes(input)? })
+    }
+}
+
+/// Nodes never contain a `=>` outside of a group, so one at the top level separates the parent.
+fn has_parent(input: ParseStream) -> bool {
+    let mut rest = input.cursor();
+
+    while let Some((tree, next)) = rest.token_tree() {
+        if let TokenTree::Punct(punct) = &tree
+            && punct.as_char() == '=' && punct.spacing() == Spacing::Joint
+            && let Some((TokenTree::Punct(arrow), _)) = next.token_tree()
+            && arrow.as_char() == '>'
+        {
+            return true;
+        }
+        rest = next;
+    }
+
+    false
+}
+
+fn parse_nodes(input: ParseStream) -> syn::Result<Vec<Node>> {
+    let mut nodes = Vec::new();
+
+    while !input.is_empty() {
+        nodes.push(input.parse()?);
+        input.parse::<Option<Token![,]>>()?;
+    }
+
+    Ok(nodes)
+}
+
+fn parse_block(input: ParseStream) -> syn::Result<Vec<Node>> {
+    let content;
+    braced!(content in input);
+    parse_nodes(&content)
+}
+
+impl Parse for Node {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Token![if]) {
+            input.parse().map(Node::If)
+        } else if input.peek(Token![for]) {
+            input.parse().map(Node::For)
+        } else if input.peek(token::Brace) {
+            let content;
+            braced!(content in input);
+            content.parse().map(Node::Expr)
+        } else {
+            input.parse().map(Node::Widget)
+        }
+    }
+}
+
+impl Parse for WidgetNode {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path: Path = input.call(Path::parse_mod_style)?;
+        let mut positional = None;
+        let mut fields = Vec::new();
+
+        if input.peek(token::Paren) {
+            let content;
+            parenthesized!(content in input);
+
+            while !content.is_empty() {
+                if content.peek(Ident) && content.peek2(Token![:]) && !content.peek2(Token![::]) {
+                    let name: Ident = content.parse()?;
+                    content.parse::<Token![:]>()?;
+                    fields.push((name, content.parse()?));
+                } else if positional.is_none() && fields.is_empty() {
+                    positional = Some(content.parse()?);
+                } else {
+                    return Err(content.error("expected `field: value`, only the first argument may be unnamed"));
+                }
+
+                if !content.is_empty() {
+                    content.parse::<Token![,]>()?;
+                }
+            }
+        }
+
+        // Components can't have children, so a block after them is the next node
+        let is_widget = path.get_ident().is_some_and(|ident| known_widget(&ident.to_string()).is_some());
+
+        let children = if is_widget && input.peek(token::Brace) {
+            Some(parse_block(input)?)
+        } else {
+            None
+        };
+
+        Ok(Self { path, positional, fields, children })
+    }
+}
+
+impl Parse for IfNode {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<Token![if]>()?;
+        let condition = input.call(Expr::parse_without_eager_brace)?;
+        let then = parse_block(input)?;
+
+        let otherwise = if input.parse::<Option<Token![else]>>()?.is_some() {
+            if input.peek(Token![if]) {
+                // `else if` nests another dynamic element in the else branch
+                vec![Node::If(input.parse()?)]
+            } else {
+                parse_block(input)?
+            }
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self { condition, then, otherwise })
+    }
+}
+
+impl Parse for ForNode {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<Token![for]>()?;
+        let pattern = input.call(Pat::parse_single)?;
+        input.parse::<Token![in]>()?;
+        let signal = input.call(Expr::parse_without_eager_brace)?;
+        let body = parse_block(input)?;
+
+        Ok(Self { pattern, signal, body })
+    }
+}
+
+
+struct Generator {
+    count: usize,
+    /// The path of the crate the expansion refers to
+    krate: TokenStream
+}
+
+impl Generator {
+    fn ident(&mut self, name: &str) -> Ident {
+        self.count += 1;
+        format_ident!("__lumi_{}_{}", name, self.count, span = Span::mixed_site())
+    }
+
+    fn nodes(&mut self, parent: &Ident, nodes: &[Node]) -> TokenStream {
+        nodes.iter().map(|node| self.node(parent, node)).collect()
+    }
+
+    fn node(&mut self, parent: &Ident, node: &Node) -> TokenStream {
+        match node {
+            Node::Widget(widget) => self.widget(parent, widget),
+            Node::Expr(expr) => quote_spanned! {expr.span()=>
+                #parent.component(#expr);
+            },
+            Node::If(node) => self.if_node(parent, node),
+            Node::For(node) => self.for_node(parent, node)
+        }
+    }
+
+    fn widget(&mut self, parent: &Ident, node: &WidgetNode) -> TokenStream {
+        let WidgetNode { path, positional, fields, children } = node;
+
+        let krate = self.krate.clone();
+        let known = path.get_ident().and_then(|ident| known_widget(&ident.to_string()));
+
+        let Some((builder, primary)) = known else {
+            if let Some(positional) = positional {
+                return syn::Error::new(positional.span(), "components only take named fields").to_compile_error();
+            }
+
+            let fields = fields.iter().map(|(name, value)| prop(&krate, name, value));
+
+            return quote! {
+                #parent.component(#path { #(#fields,)* });
+            };
+        };
+
+        // Set on the element instead of the widget builder
+        let (element_fields, fields): (Vec<_>, Vec<_>) = fields.iter().partition(|(name, _)| ELEMENT_FIELDS.contains(&name.to_string().as_str()));
+        let fields = fields.into_iter().map(|(name, value)| prop(&krate, name, value));
+        let element_fields = element_fields.into_iter().map(|(name, value)| {
+            quote_spanned! {value.span()=> .#name(#krate::macros::IntoProp::into_prop(#value)) }
+        });
+
+        let positional = match (positional, primary) {
+            (None, _) => None,
+            (Some(value), Some(primary)) => {
+                let primary = Ident::new(primary, path.span());
+                Some(quote_spanned! {value.span()=> #primary: #krate::macros::IntoProp::into_prop(#value), })
+            },
+            (Some(value), None) => {
+                return syn::Error::new(value.span(), format!("`{}` only takes named fields", quote!(#path))).to_compile_error();
+            }
+        };
+
+        let element = self.ident("element");
+        let children = self.nodes(&element, children.as_deref().unwrap_or_default());
+
+        quote! {
+            let #element = #parent.child_widget(
+                #krate::widgets::widget_builder::WidgetBuilder::from(#krate::#builder {
+                    #positional
+                    #(#fields,)*
+                    ..::core::default::Default::default()
+                })
+            )#(#element_fields)*;
+            #children
+        }
+    }
+
+    fn if_node(&mut self, parent: &Ident, node: &IfNode) -> TokenStream {
+        let IfNode { condition, then, otherwise } = node;
+        let krate = self.krate.clone();
+
+        let inner = Ident::new("__lumi_parent", Span::mixed_site());
+        let value = Ident::new("__lumi_value", Span::mixed_site());
+        let then = self.nodes(&inner, then);
+        let otherwise = self.nodes(&inner, otherwise);
+
+        dynamic(&krate, parent, condition, quote! {
+            move |#value: &bool, #inner: #krate::elements::element_builder::ElementBuilder| {
+                if *#value {
+                    #then
+                } else {
+                    #otherwise
+                }
+            }
+        })
+    }
+
+    fn for_node(&mut self, parent: &Ident, node: &ForNode) -> TokenStream {
+        let ForNode { pattern, signal, body } = node;
+        let krate = self.krate.clone();
+
+        let inner = Ident::new("__lumi_parent", Span::mixed_site());
+        let value = Ident::new("__lumi_value", Span::mixed_site());
+        let body = self.nodes(&inner, body);
+
+        dynamic(&krate, parent, signal, quote! {
+            move |#value, #inner: #krate::elements::element_builder::ElementBuilder| {
+                for #pattern in #value {
+                    #body
+                }
+            }
+        })
+    }
+}
+
+fn dynamic(krate: &TokenStream, parent: &Ident, signal: &Expr, callback: TokenStream) -> TokenStream {
+    quote! {
+        #parent.child(#krate::elements::element_builder::ElementBuilder::from(
+            ::std::sync::Arc::new(#krate::elements::dynamic::DynamicElementBuilder::new(
+                ::core::clone::Clone::clone(&(#signal)),
+                #callback
+            ))
+        ));
+    }
+}
+
+fn prop(krate: &TokenStream, name: &Ident, value: &Expr) -> TokenStream {
+    quote_spanned! {value.span()=> #name: #krate::macros::IntoProp::into_prop(#value) }
+}
+
+/// Fields of widgets which are set through a method of their `ElementBuilder`.
+const ELEMENT_FIELDS: &[&str] = &["z_index"];
+
+/// The builder of a widget relative to the crate root, and the field set by its unnamed argument.
+fn known_widget(name: &str) -> Option<(TokenStream, Option<&'static str>)> {
+    let found = match name {
+        "Window" => (quote!(elements::window::WindowBuilder), None),
+        "Rectangle" => (quote!(widgets::rectangle::RectangleBuilder), None),
+        "Text" => (quote!(widgets::text::TextBuilder), Some("text")),
+        "Image" => (quote!(widgets::image::ImageBuilder), Some("source")),
+        "Svg" => (quote!(widgets::svg::SvgBuilder), Some("source")),
+        "Interact" => (quote!(widgets::interact::InteractBuilder), None),
+        _ => return None
+    };
+
+    Some(found)
+}
@@ -1,8 +1,10 @@
-use std::{ops::Add, sync::Arc, time::Duration};
+use std::{sync::Arc, time::Duration};
 
 use lumi_ui::lumi2d::renderer::{objects::Rounding, text::TextOptions};
 use lumi_ui::lumi2d::types::Position;
-use lumi_ui::{backend::Backend, byte_source::ByteSource, callback::Callback, elements::{dynamic::DynamicElementBuilder, element_builder::ElementBuilder, window::{WindowBuilder, WindowState}}, signals::{Signal, SignalTrait, Slot}, widgets::{image::ImageBuilder, interact::InteractBuilder, rectangle::RectangleBuilder, svg::SvgBuilder, text::TextBuilder, widget_builder::WidgetBuilder}};
+use lumi_ui::layout::anchor::Anchors;
+use lumi_ui::animations::{easings::EasingFunction, layout::LayoutAnimation, transition::{Transition, TransitionEffect}};
+use lumi_ui::{view, backend::Backend, byte_source::ByteSource, callback::Callback, elements::{component::Component, dynamic::DynamicElementBuilder, element_builder::ElementBuilder, group::GroupBuilder, window::{WindowBuilder, WindowState}}, signals::{Signal, SignalTrait, Slot}, widgets::{image::ImageBuilder, interact::InteractBuilder, rectangle::RectangleBuilder, svg::SvgBuilder, text::TextBuilder, widget_builder::WidgetBuilder}};
 use simple_logger::SimpleLogger;
 
 fn main() {
@@ -34,15 +36,23 @@ fn main() {
                 width: window_state.dimensions.relative(|dims| dims.width.saturating_sub(200)),
                 height: window_state.dimensions.relative(|dims| dims.height.saturating_sub(200)),
                 color: Signal::constant(0xDDDDDDDD),
-                rounding: Signal::constant(Some(Rounding::new_uniform(10)))
+                rounding: Signal::constant(Some(Rounding::new_uniform(10))),
+                animate_layout: Some(LayoutAnimation::new(Duration::from_millis(150), EasingFunction::Linear))
+            };
+            // Everything in the group is positioned relative to its top left corner
+            let group = GroupBuilder {
+                x: rect1.x.relative(|x| (x + 50) as f32),
+                y: rect1.y.relative(|y| (y + 50) as f32),
+                ..Default::default()
             };
             let rect2 = RectangleBuilder {
-                x: rect1.x.relative(|x| x + 50),
-                y: rect1.y.relative(|y| y + 50),
+                x: Signal::constant(0),
+                y: Signal::constant(0),
                 width: rect1.width.clone(),
                 height: rect1.height.relative(|h| h.saturating_sub(100)),
                 color: Signal::constant(0xFF11EEAA),
-                rounding: Signal::constant(None)
+                rounding: Signal::constant(None),
+                ..Default::default()
             };
             let text1 = TextBuilder {
                 x: rect2.x.clone(),
@@ -55,22 +65,25 @@ fn main() {
                     italic: true,
                     underline: true,
                     ..Default::default()
-                })
+                }),
+                ..Default::default()
             };
             let image1 = ImageBuilder {
                 x: rect2.x.clone(),
                 y: rect2.y.relative(|y| y + 80),
                 width: rect2.width.clone(),
                 height: rect2.height.relative(|h| h.saturating_sub(80)),
-                source: Signal::constant(ByteSource::bytes(include_bytes!("../../nori.gif")))
+                source: Signal::constant(ByteSource::bytes(include_bytes!("../../nori.gif"))),
+                ..Default::default()
             };
             let svg1 = SvgBuilder {
-                x: (rect2.x.clone(), rect2.width.clone()).relative(|(x, w)| (**x).add(**w as i32).saturating_sub(70)),
+                x: rect2.width.relative(|w| (*w as i32).saturating_sub(70)),
                 y: rect2.y.relative(|y| y + 10),
                 width: Signal::constant(60),
                 height: Signal::constant(60),
                 color: Signal::constant(0xEEEEEEFF),
-                source: Signal::constant(ByteSource::bytes(include_bytes!("../../home.svg")))
+                source: Signal::constant(ByteSource::bytes(include_bytes!("../../home.svg"))),
+                ..Default::default()
             };
             let interact1 = InteractBuilder {
                 x: Signal::constant(10),
@@ -100,35 +113,42 @@ fn main() {
                     0xAAAAAAFF
                 }),
                 rounding: Signal::constant(None),
+                ..Default::default()
             };
 
             let switch_state = Signal::new(false);
 
+            window.child_widget(rect1.into());
+
+            window.group(group).child_widget(
+                rect2.into()
+            ).child_widget(
+                text1.into()
+            ).child_widget(
+                image1.into()
+            ).child_widget(
+                svg1.into()
+            );
+
+            window.child_widget(
+                interact1.into()
+            ).child_widget(
+                rect3.into()
+            );
+
             let state_clone = switch_state.clone();
-            let switch = InteractBuilder {
-                x: Signal::constant(20),
-                y: Signal::constant(300),
-                width: Signal::constant(50),
-                height: Signal::constant(50),
-                clicked: Some(Callback::new(move || {
-                    let current = *state_clone.get();
-                    state_clone.set(!current);
-                })),
-                ..Default::default()
-            };
-            let switch_bg = RectangleBuilder {
-                x: Signal::constant(20),
-                y: Signal::constant(300),
-                width: Signal::constant(50),
-                height: Signal::constant(50),
-                color: switch_state.relative(|state| {
-                    if *state {
-                        0x00FF00FF
-                    } else {
-                        0xFF0000FF
-                    }
-                }),
-                ..Default::default()
+            view! { window =>
+                Interact(
+                    x: 20, y: 300, width: 50, height: 50,
+                    clicked: Callback::new(move || {
+                        let current = *state_clone.get();
+                        state_clone.set(!current);
+                    })
+                )
+                Rectangle(
+                    x: 20, y: 300, width: 50, height: 50,
+                    color: switch_state.relative(|state| if *state { 0x00FF00FF } else { 0xFF0000FF })
+                )
             };
 
             let dynamic_element = Arc::new(DynamicElementBuilder::new(
@@ -140,9 +160,8 @@ fn main() {
                         };
 
                         let text = TextBuilder {
-                            x: window.state.dimensions.relative(|dim| dim.width as i32 - 100),
-                            y: Signal::constant(20),
                             width: Signal::constant(50),
+                            anchors: Some(Signal::constant(Anchors::default().with_right(50).with_top(20))),
                             text: Signal::constant("Hehe window".to_string()),
                             ..Default::default()
                         };
@@ -160,102 +179,104 @@ fn main() {
                         parent.child_widget(rect.into());
                     }
                 }
-            ));
+            ).with_transition(Transition::new(
+                TransitionEffect::Slide { x: -40.0, y: 0.0 },
+                TransitionEffect::Fade,
+                Duration::from_millis(300),
+                EasingFunction::Linear
+            )));
 
-            
-            window.child_widget(
-                rect1.into()
-            ).child_widget(
-                rect2.into()
-            ).child_widget(
-                text1.into()
-            ).child_widget(
-                image1.into()
-            ).child_widget(
-                svg1.into()
-            ).child_widget(
-                interact1.into()
-            ).child_widget(
-                rect3.into()
-            ).child_widget(
-                switch.into()
-            ).child_widget(
-                switch_bg.into()
-            ).child(dynamic_element.into());
+            window.child(dynamic_element.into());
 
-            slider(window.clone());
+            let _slider_progress = window.component(Slider { x: 150, y: 20, width: 200, height: 30 });
 
             root
         });
     }).expect("Failed to initialize LumiUI!");
 }
 
-fn slider(parent: ElementBuilder) {
-    let (x, y, width, height) = (150, 20, 200, 30);
-    let point_x = Signal::new(x - height as i32 / 2);
-    let progress = Signal::new(0.0);
+struct Slider {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32
+}
+
+impl Component for Slider {
+    /// The progress of the slider, from 0.0 to 1.0
+    type Output = Signal<f64>;
 
-    let rect = RectangleBuilder {
-        x: Signal::constant(x),
-        y: Signal::constant(y + height as i32 / 4),
-        width: Signal::constant(width),
-        height: Signal::constant(height/2),
-        color: Signal::constant(0xAAAAAAFF),
-        rounding: Signal::constant(Some(Rounding::new_uniform(10))),
-        ..Default::default()
-    };
+    fn build(self, parent: &ElementBuilder) -> Signal<f64> {
+        let Slider { x, y, width, height } = self;
+        let point_x = Signal::new(x - height as i32 / 2);
+        let progress = Signal::new(0.0);
+        let output = progress.clone();
 
-    let overlay = RectangleBuilder {
-        color: Signal::constant(0xFF33FFFF),
-        width: progress.relative(move |prog| {
-            (width as f64 * prog) as u32
-        }).animate(Duration::from_secs(3), lumi_ui::animations::easings::EasingFunction::Linear),
-        ..rect.clone()
-    };
+        let rect = RectangleBuilder {
+            x: Signal::constant(x),
+            y: Signal::constant(y + height as i32 / 4),
+            width: Signal::constant(width),
+            height: Signal::constant(height/2),
+            color: Signal::constant(0xAAAAAAFF),
+            rounding: Signal::constant(Some(Rounding::new_uniform(10))),
+            ..Default::default()
+        };
 
-    let point = RectangleBuilder {
-        x: point_x.clone(),
-        y: Signal::constant(y),
-        width: Signal::constant(height),
-        height: Signal::constant(height),
-        color: Signal::constant(0xFF33FFFF),
-        rounding: Signal::constant(Some(Rounding::new_uniform(100)))
-    };
+        let overlay = RectangleBuilder {
+            color: Signal::constant(0xFF33FFFF),
+            width: progress.relative(move |prog| {
+                (width as f64 * prog) as u32
+            }).animate(Duration::from_secs(3), EasingFunction::Linear),
+            ..rect.clone()
+        };
 
-    let text = TextBuilder {
-        x: Signal::constant(x + width as i32 + 20),
-        y: Signal::constant(y),
-        text: progress.relative(|prog| {
-            format!("{:.1}%", prog * 100.0)
-        }),
-        options: Signal::constant(TextOptions {
-            size: 20.0,
+        let point = RectangleBuilder {
+            x: point_x.clone(),
+            y: Signal::constant(y),
+            width: Signal::constant(height),
+            height: Signal::constant(height),
+            color: Signal::constant(0xFF33FFFF),
+            rounding: Signal::constant(Some(Rounding::new_uniform(100))),
             ..Default::default()
-        }),
-        width: Signal::constant(80),
-        ..Default::default()
-    };
+        };
 
-    let interact = InteractBuilder {
-        x: Signal::constant(x),
-        y: Signal::constant(y),
-        width: Signal::constant(width),
-        height: Signal::constant(height),
-        mouse_drag: Some(Slot::new(move |pos: &Position<f64>| {
-            let new_x = (pos.x as i32).clamp(x, x + width as i32);
-            point_x.set(new_x - height as i32 / 2);
+        let text = TextBuilder {
+            x: Signal::constant(x + width as i32 + 20),
+            y: Signal::constant(y),
+            text: progress.relative(|prog| {
+                format!("{:.1}%", prog * 100.0)
+            }),
+            options: Signal::constant(TextOptions {
+                size: 20.0,
+                ..Default::default()
+            }),
+            width: Signal::constant(80),
+            ..Default::default()
+        };
+
+        let interact = InteractBuilder {
+            x: Signal::constant(x),
+            y: Signal::constant(y),
+            width: Signal::constant(width),
+            height: Signal::constant(height),
+            mouse_drag: Some(Slot::new(move |pos: &Position<f64>| {
+                let new_x = (pos.x as i32).clamp(x, x + width as i32);
+                point_x.set(new_x - height as i32 / 2);
+
+                let offset = new_x - x;
+                progress.set(offset as f64 / width as f64);
+            })),
+            ..Default::default()
+        };
 
-            let offset = new_x - x;
-            progress.set(offset as f64 / width as f64);
-        })),
-        ..Default::default()
-    };
 
+        parent
+        .child_widget(rect.into())
+        .child_widget(overlay.into())
+        .child_widget(interact.into())
+        .child_widget(point.into())
+        .child_widget(text.into());
 
-    parent
-    .child_widget(rect.into())
-    .child_widget(overlay.into())
-    .child_widget(interact.into())
-    .child_widget(point.into())
-    .child_widget(text.into());
+        output
+    }
 }
\ No newline at end of file